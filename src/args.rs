@@ -1,39 +1,391 @@
 // Cargo Modules
-use clap::{Parser, ArgAction};
+use clap::{Parser, ArgAction, ValueEnum};
+
+// Project Modules
+use crate::print::human::BlockSize;
 
 #[derive(Parser, Debug)]
+#[command(args_override_self = true, disable_help_flag = true)]
 pub struct Args {
     /// Path of File, Directory or Symlink
     #[arg(value_name = "FILE(s)")]
     pub paths: Vec<String>,
-    
-    /// Currently equivalenal to -A, due to using std::fs instead of libc. Otherwise:
-    /// Do not ignore files starting with "."
+
+    /// Do not ignore entries starting with "."
     #[arg(short='a', long="all", action = ArgAction::SetTrue)]
     pub all: bool,
 
-    // Use column listing format
-    //#[arg(short='x', action = ArgAction::SetTrue)]
-    //pub horizontal: bool,
+    /// List entries by columns
+    #[arg(short='C', action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "long", "horizontal", "one_per_line", "commas",
+        "numeric_uid_gid", "long_without_owner", "long_without_group"])]
+    pub columns: bool,
 
     /// Use long listing format
-    #[arg(short='l', action = ArgAction::SetTrue)]
+    #[arg(short='l', action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "columns", "horizontal", "one_per_line", "commas"])]
     pub long: bool,
-    
-    // Use row listing format
-    //#[arg(short='x', action = ArgAction::SetTrue)]
-    //pub horizontal: bool,
+
+    /// List entries by lines instead of by columns
+    #[arg(short='x', action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "columns", "long", "one_per_line", "commas",
+        "numeric_uid_gid", "long_without_owner", "long_without_group"])]
+    pub horizontal: bool,
+
+    /// Like -l, but list numeric user and group IDs
+    #[arg(short='n', long="numeric-uid-gid", action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "columns", "horizontal", "one_per_line", "commas"])]
+    pub numeric_uid_gid: bool,
+
+    /// Like -l, but do not list owner
+    #[arg(short='g', action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "columns", "horizontal", "one_per_line", "commas"])]
+    pub long_without_owner: bool,
+
+    /// Like -l, but do not list group information
+    #[arg(short='o', action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "columns", "horizontal", "one_per_line", "commas"])]
+    pub long_without_group: bool,
+
+    /// In a long listing, don't print group names
+    #[arg(short='G', long="no-group", action = ArgAction::SetTrue)]
+    pub no_group: bool,
+
+    /// With -l, print the author of each file
+    #[arg(long="author", action = ArgAction::SetTrue)]
+    pub author: bool,
+
+    /// List one file per line
+    #[arg(short='1', action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "columns", "long", "horizontal", "commas",
+        "numeric_uid_gid", "long_without_owner", "long_without_group"])]
+    pub one_per_line: bool,
+
+    /// Fill width with a comma separated list of entries
+    #[arg(short='m', action = ArgAction::SetTrue,
+      overrides_with_all = ["format", "columns", "long", "horizontal", "one_per_line",
+        "numeric_uid_gid", "long_without_owner", "long_without_group"])]
+    pub commas: bool,
+
+    /// Output format WORD
+    #[arg(long="format", value_name="WORD", value_enum,
+      overrides_with_all = ["columns", "long", "horizontal", "one_per_line", "commas",
+        "numeric_uid_gid", "long_without_owner", "long_without_group"])]
+    pub format: Option<FormatWord>,
 
     /// Do not list implied . and ..
     #[arg(short='A', long="almost-all", action = ArgAction::SetTrue)]
     pub almost_all: bool,
 
-    /// When showing file information for symbolic links, show information for the 
+    /// Do not list implied entries ending with ~
+    #[arg(short='B', long="ignore-backups", action = ArgAction::SetTrue)]
+    pub ignore_backups: bool,
+
+    /// Do not list implied entries matching shell PATTERN
+    #[arg(short='I', long="ignore", value_name="PATTERN", action = ArgAction::Append)]
+    pub ignore: Vec<String>,
+
+    /// Do not list implied entries matching shell PATTERN (overridden by -a or -A)
+    #[arg(long="hide", value_name="PATTERN", action = ArgAction::Append)]
+    pub hide: Vec<String>,
+
+    /// Hide or dim entries ignored by .gitignore, .git/info/exclude and .ignore files;
+    /// without MODE: hide
+    #[arg(long="git-ignore", value_name="MODE", value_enum, num_args = 0..=1,
+      require_equals = true, default_missing_value = "hide")]
+    pub git_ignore: Option<GitIgnoreWord>,
+
+    /// When showing file information for symbolic links, show information for the
     /// file referenced instead
-    #[arg(short='L', long="dereference", action = ArgAction::SetTrue)]
+    #[arg(short='L', long="dereference", action = ArgAction::SetTrue,
+      overrides_with_all = ["dereference_command_line", "dereference_command_line_symlink_to_dir"])]
     pub dereference: bool,
 
+    /// Follow symbolic links listed on the command line
+    #[arg(short='H', long="dereference-command-line", action = ArgAction::SetTrue,
+      overrides_with_all = ["dereference", "dereference_command_line_symlink_to_dir"])]
+    pub dereference_command_line: bool,
+
+    /// Follow each command line symbolic link that points to a directory
+    #[arg(long="dereference-command-line-symlink-to-dir", action = ArgAction::SetTrue,
+      overrides_with_all = ["dereference", "dereference_command_line"])]
+    pub dereference_command_line_symlink_to_dir: bool,
+
     /// List subdirectories recursively
     #[arg(short='R', long="recursive", action = ArgAction::SetTrue)]
     pub recursive: bool,
-}
\ No newline at end of file
+
+    /// With -l, show every hop of symbolic links. Relative hops are written
+    /// as "->", absolute ones as "=>"
+    #[arg(long="link-chain", action = ArgAction::SetTrue)]
+    pub link_chain: bool,
+
+    /// List directories themselves, not their contents. Overrides -R
+    #[arg(short='d', long="directory", action = ArgAction::SetTrue)]
+    pub directory: bool,
+
+    /// Reverse order while sorting
+    #[arg(short='r', long="reverse", action = ArgAction::SetTrue)]
+    pub reverse: bool,
+
+    /// Sort by WORD instead of name
+    #[arg(long="sort", value_name="WORD", value_enum,
+      overrides_with_all = ["sort_size", "sort_time", "sort_extension", "sort_version", "unsorted"])]
+    pub sort: Option<SortWord>,
+
+    /// Sort by file size, largest first
+    #[arg(short='S', action = ArgAction::SetTrue,
+      overrides_with_all = ["sort", "sort_time", "sort_extension", "sort_version", "unsorted"])]
+    pub sort_size: bool,
+
+    /// Sort by time, newest first; see --time
+    #[arg(short='t', action = ArgAction::SetTrue,
+      overrides_with_all = ["sort", "sort_size", "sort_extension", "sort_version", "unsorted"])]
+    pub sort_time: bool,
+
+    /// Sort alphabetically by entry extension
+    #[arg(short='X', action = ArgAction::SetTrue,
+      overrides_with_all = ["sort", "sort_size", "sort_time", "sort_version", "unsorted"])]
+    pub sort_extension: bool,
+
+    /// Natural sort of (version) numbers within text
+    #[arg(short='v', action = ArgAction::SetTrue,
+      overrides_with_all = ["sort", "sort_size", "sort_time", "sort_extension", "unsorted"])]
+    pub sort_version: bool,
+
+    /// Do not sort; list entries in directory order
+    #[arg(short='U', action = ArgAction::SetTrue,
+      overrides_with_all = ["sort", "sort_size", "sort_time", "sort_extension", "sort_version"])]
+    pub unsorted: bool,
+
+    /// Select which timestamp is shown with -l and used by -t.
+    /// Without -l and an explicit sort option, sort by that time
+    #[arg(long="time", value_name="WORD", value_enum, overrides_with_all = ["atime", "ctime"])]
+    pub time: Option<TimeWord>,
+
+    /// Use time of last access, same as --time=atime
+    #[arg(short='u', action = ArgAction::SetTrue, overrides_with_all = ["time", "ctime"])]
+    pub atime: bool,
+
+    /// Use time of last status change, same as --time=ctime
+    #[arg(short='c', action = ArgAction::SetTrue, overrides_with_all = ["time", "atime"])]
+    pub ctime: bool,
+
+    /// Color the output WHEN; without WHEN: always.
+    /// Defaults to the environment (CLICOLOR_FORCE, NO_COLOR, CLICOLOR), then to auto
+    #[arg(long="color", value_name="WHEN", value_enum, num_args = 0..=1,
+      require_equals = true, default_missing_value = "always")]
+    pub color: Option<When>,
+
+    /// Append indicator (one of */=>@|) to entries WHEN; without WHEN: always
+    #[arg(short='F', long="classify", value_name="WHEN", value_enum, num_args = 0..=1,
+      require_equals = true, default_missing_value = "always",
+      overrides_with_all = ["slash", "file_type", "indicator_style"])]
+    pub classify: Option<When>,
+
+    /// Append / indicator to directories
+    #[arg(short='p', action = ArgAction::SetTrue,
+      overrides_with_all = ["classify", "file_type", "indicator_style"])]
+    pub slash: bool,
+
+    /// Likewise, except do not append '*'
+    #[arg(long="file-type", action = ArgAction::SetTrue,
+      overrides_with_all = ["classify", "slash", "indicator_style"])]
+    pub file_type: bool,
+
+    /// Append indicator with style WORD to entry names
+    #[arg(long="indicator-style", value_name="WORD", value_enum,
+      overrides_with_all = ["classify", "slash", "file_type"])]
+    pub indicator_style: Option<IndicatorWord>,
+
+    /// Use quoting style WORD for entry names
+    #[arg(long="quoting-style", value_name="WORD", value_enum,
+      overrides_with_all = ["quote_name", "literal", "escape"])]
+    pub quoting_style: Option<QuotingWord>,
+
+    /// Enclose entry names in double quotes
+    #[arg(short='Q', long="quote-name", action = ArgAction::SetTrue,
+      overrides_with_all = ["quoting_style", "literal", "escape"])]
+    pub quote_name: bool,
+
+    /// Print entry names without quoting
+    #[arg(short='N', long="literal", action = ArgAction::SetTrue,
+      overrides_with_all = ["quoting_style", "quote_name", "escape"])]
+    pub literal: bool,
+
+    /// Print C-style escapes for nongraphic characters
+    #[arg(short='b', long="escape", action = ArgAction::SetTrue,
+      overrides_with_all = ["quoting_style", "quote_name", "literal"])]
+    pub escape: bool,
+
+    /// Print ? instead of nongraphic characters (default on terminals)
+    #[arg(short='q', long="hide-control-chars", action = ArgAction::SetTrue,
+      overrides_with = "show_control_chars")]
+    pub hide_control_chars: bool,
+
+    /// Show nongraphic characters as-is (default unless output is a terminal)
+    #[arg(long="show-control-chars", action = ArgAction::SetTrue, overrides_with = "hide_control_chars")]
+    pub show_control_chars: bool,
+
+    /// Print bidi controls and invisible characters in names as <U+XXXX>,
+    /// and warn about names that look identical to others
+    #[arg(long="reveal-invisible", action = ArgAction::SetTrue)]
+    pub reveal_invisible: bool,
+
+    /// Instead of listing, output shell code to set LS_COLORS from the dircolors
+    /// database FILE, or from the built-in database
+    #[arg(long="dircolors", action = ArgAction::SetTrue)]
+    pub dircolors: bool,
+
+    /// Output the built-in dircolors database
+    #[arg(long="print-database", action = ArgAction::SetTrue)]
+    pub print_database: bool,
+
+    /// With --dircolors: output Bourne shell code (default, unless $SHELL is a csh)
+    #[arg(long="bourne-shell", visible_alias="sh", action = ArgAction::SetTrue, overrides_with = "c_shell")]
+    pub bourne_shell: bool,
+
+    /// With --dircolors: output C shell code
+    #[arg(long="c-shell", visible_alias="csh", action = ArgAction::SetTrue, overrides_with = "bourne_shell")]
+    pub c_shell: bool,
+
+    /// With -l and -s, print sizes like 1K 234M 2G etc.
+    #[arg(short='h', long="human-readable", action = ArgAction::SetTrue,
+      overrides_with_all = ["si", "block_size"])]
+    pub human_readable: bool,
+
+    /// Likewise, but use powers of 1000 not 1024
+    #[arg(long="si", action = ArgAction::SetTrue, overrides_with_all = ["human_readable", "block_size"])]
+    pub si: bool,
+
+    /// With -l, scale sizes by SIZE when printing them; e.g., '--block-size=M'.
+    /// SIZE is an integer with an optional unit (K, M, G, ... or KB, MB, ... for powers of 1000)
+    #[arg(long="block-size", value_name="SIZE", value_parser = BlockSize::parse,
+      overrides_with_all = ["human_readable", "si"])]
+    pub block_size: Option<BlockSize>,
+
+    /// Print the index number of each file
+    #[arg(short='i', long="inode", action = ArgAction::SetTrue)]
+    pub inode: bool,
+
+    /// Print the allocated size of each file, in blocks
+    #[arg(short='s', long="size", action = ArgAction::SetTrue)]
+    pub size: bool,
+
+    /// Set output width to COLS. 0 means no limit
+    #[arg(short='w', long="width", value_name="COLS")]
+    pub width: Option<usize>,
+
+    /// Assume tab stops at each COLS instead of padding with spaces only
+    #[arg(short='T', long="tabsize", value_name="COLS")]
+    pub tabsize: Option<usize>,
+
+    /// Print help
+    #[arg(long="help", action = ArgAction::Help)]
+    pub help: Option<bool>,
+
+    /// List all entries in directory order, same as -a -U
+    #[arg(short='f', action = ArgAction::SetTrue)]
+    pub unsorted_all: bool,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum SortWord {
+    /// Directory order (-U)
+    None,
+    /// Largest first (-S)
+    Size,
+    /// Newest first (-t)
+    Time,
+    /// Natural sort of version numbers (-v)
+    Version,
+    /// By extension (-X)
+    Extension,
+    /// Narrowest name first
+    Width,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum TimeWord {
+    /// Time of last access (-u)
+    #[value(alias = "access", alias = "use")]
+    Atime,
+    /// Time of last status change (-c)
+    #[value(alias = "status")]
+    Ctime,
+    /// Time of last modification (default)
+    #[value(alias = "modification")]
+    Mtime,
+    /// Time of creation, if supported by the filesystem
+    #[value(alias = "creation")]
+    Birth,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum FormatWord {
+    /// Same as -x
+    #[value(alias = "horizontal")]
+    Across,
+    /// Same as -m
+    Commas,
+    /// Same as -l
+    #[value(alias = "verbose")]
+    Long,
+    /// Same as -1
+    SingleColumn,
+    /// Same as -C
+    Vertical,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum GitIgnoreWord {
+    /// Do not list ignored entries
+    Hide,
+    /// List ignored entries dimmed, if colors are enabled
+    Dim,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum IndicatorWord {
+    /// No indicators (default)
+    None,
+    /// Same as -p
+    Slash,
+    /// Same as --file-type
+    FileType,
+    /// Same as -F
+    Classify,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum QuotingWord {
+    /// Names as they are (-N)
+    Literal,
+    /// Like c, with the quotes of the locale
+    Locale,
+    /// Quote names for the shell, if needed
+    Shell,
+    /// Always quote names for the shell
+    ShellAlways,
+    /// Like shell, with $'' escapes for nonprintable characters (default on terminals)
+    ShellEscape,
+    /// Like shell-always, with $'' escapes for nonprintable characters
+    ShellEscapeAlways,
+    /// C string with escapes (-Q)
+    C,
+    /// C escapes without quotes (-b)
+    Escape,
+    /// Like locale, but with "" outside of UTF-8 locales
+    Clocale,
+}
+
+#[derive(ValueEnum, Clone, Copy, PartialEq, Debug)]
+pub enum When {
+    #[value(alias = "yes", alias = "force")]
+    Always,
+    /// Only if stdout is a terminal
+    #[value(alias = "tty", alias = "if-tty")]
+    Auto,
+    #[value(alias = "no", alias = "none")]
+    Never,
+}
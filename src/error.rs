@@ -6,6 +6,8 @@ pub enum Error {
   #[from]
   FS(super::filesystem::Error),
   #[from]
+  Print(super::print::Error),
+  #[from]
   IO(std::io::Error)
 }
 
@@ -15,6 +17,7 @@ impl std::fmt::Display for Error {
   fn fmt(&self, fmt: &mut std::fmt::Formatter<'_>) -> std::result::Result<(), std::fmt::Error> {
     match self {
       Self::FS(inner) => write!(fmt, "{}", inner),
+      Self::Print(inner) => write!(fmt, "{}", inner),
       Self::IO(inner) => write!(fmt, "{}", inner)
     }
   }
@@ -41,11 +41,11 @@ impl From<bool> for ExitStatus {
 }
 
 pub trait IntoExitStatus {
-  fn into_exit_status(&self, cli_arg: bool) -> ExitStatus;
+  fn into_exit_status(self, cli_arg: bool) -> ExitStatus;
 }
 
 impl IntoExitStatus for Error {
-  fn into_exit_status(&self, cli_arg: bool) -> ExitStatus {
+  fn into_exit_status(self, cli_arg: bool) -> ExitStatus {
     ExitStatus::from(cli_arg)
   }
 }
\ No newline at end of file
@@ -0,0 +1,26 @@
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum Dereference {
+  /// Show symbolic links themselves
+  Never = 0,
+
+  /// Follow symbolic links given on the command line (-H)
+  CommandLineArguments,
+
+  /// Follow symbolic links given on the command line, if they point to a directory
+  #[default]
+  CommandLineSymlinkToDir,
+
+  /// Follow every symbolic link (-L)
+  Always
+}
+
+impl Dereference {
+  /// Whether the metadata of a link is taken from its target
+  pub fn follows(&self, cli_arg: bool) -> bool {
+    match self {
+      Dereference::Never => false,
+      Dereference::CommandLineArguments | Dereference::CommandLineSymlinkToDir => cli_arg,
+      Dereference::Always => true
+    }
+  }
+}
@@ -7,6 +7,8 @@ use super::{ExitStatus, IntoExitStatus};
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+// Every variant names the kind of error
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug, From)]
 pub enum Error {
   PathInvalidUTF8Error {
@@ -42,7 +44,7 @@ pub enum Error {
 }
 
 impl IntoExitStatus for Error {
-  fn into_exit_status(&self, cli_arg: bool) -> ExitStatus {
+  fn into_exit_status(self, cli_arg: bool) -> ExitStatus {
     ExitStatus::from(cli_arg)
   }
 }
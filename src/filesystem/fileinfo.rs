@@ -1,12 +1,15 @@
 // Standard Libraries
-use std::borrow::Cow;
 use std::fs::Metadata;
 use std::os::unix::fs::FileTypeExt;
-use std::rc::{Rc, Weak};
 
 // Cargo Modules 
 use unicode_width::UnicodeWidthStr;
 
+// Project Modules
+use super::{LinkChain, NameStyle};
+
+// Named like the file types of POSIX
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Default, PartialEq, Debug)]
 pub enum FileType {
   #[default]
@@ -51,26 +54,34 @@ pub struct FileInfo {
   pub link_name: Option<String>,
   /// Contains Access to: FileType, Permissions, stat-Objects 
   pub metadata: Option<Metadata>,
-  /// Inode of File - 0 when command line argument!
-  #[allow(dead_code)]
+  /// Inode of File - 0 if unknown
   pub inode: u64,
   /// Cached FileType, differentiates between Directories and Directories passed as an argument
   pub file_type: FileType,
-  /// Metadata of link target, for coloring
-  #[allow(dead_code)]
+  /// Metadata of link target, for coloring and indicators
   pub link_metadata: Option<Metadata>,
-  /// Cached screen width (quotes included)
+  /// Every hop of a symbolic link (--link-chain)
+  pub link_chain: Option<LinkChain>,
+  /// Ignored by git, but listed dimmed (--git-ignore=dim)
+  pub dimmed: bool,
+  /// Type indicator after the name (-F, -p, --file-type)
+  pub indicator: Option<char>,
+  /// Cached screen width (quotes, escapes and indicator included)
   pub width: usize,
-  /// Whether the file name has to be quoted on output
-  quoting: bool
+  /// File name in the quoting style
+  quoted_name: String
 }
 
 impl FileInfo {
   pub fn new(name: String, link_name: Option<String>, metadata: Option<Metadata>, 
-    link_metadata: Option<Metadata>, inode: u64, file_type: FileType) -> Self {
-    let quoting = name.chars().any(|c| c.is_whitespace());
-    let width = UnicodeWidthStr::width(name.as_str()) 
-      + if quoting { UnicodeWidthStr::width("''") } else { 0 };
+    link_metadata: Option<Metadata>, inode: u64, file_type: FileType, name_style: NameStyle) -> Self {
+    let quoted_name: String = name_style.quote(&name);
+    let indicator: Option<char> = match &metadata {
+      Some(metadata) => name_style.indicator_style.indicator(metadata),
+      None => name_style.indicator_style.indicator_for_type(&file_type)
+    };
+    let width = UnicodeWidthStr::width(quoted_name.as_str()) 
+      + indicator.is_some() as usize;
 
       FileInfo{
         name,
@@ -78,61 +89,21 @@ impl FileInfo {
         metadata,
         inode,
         link_metadata,
+        link_chain: None,
+        dimmed: false,
         file_type,
+        indicator,
         width,
-        quoting
+        quoted_name
       }
   }
 
   pub fn is_directory(&self) -> bool{
-    match &self.file_type {
-      FileType::Directory | FileType::ArgDirectory => true,
-      _ => false
-    }
+    matches!(&self.file_type, FileType::Directory | FileType::ArgDirectory)
   }
 
   pub fn quoted_name(&self) -> String {
-    if self.quoting {
-      format!("'{}'", &self.name)
-    } else {
-      self.name.clone()
-    }
-  }
-}
-
-pub enum SortType {
-  Name = 0,
-  //Size,
-  //Time,
-  //None
-}
-
-trait SortFileInfoCollection {
-  fn sort(&mut self, sort_type: SortType);
-}
-
-impl SortFileInfoCollection for Vec<Weak<FileInfo>> {
-  fn sort(&mut self, sort_type: SortType) {
-    match sort_type {
-      SortType::Name => self.sort_by_key(|f| Cow::from(f.upgrade().unwrap().name.to_lowercase())),
-      //SortType::Size => ,
-      //SortType::Time => ,
-      //SortType::None => 
-    }
+    self.quoted_name.clone()
   }
 }
 
-pub trait SortedFileInfoCollection {
-    fn sorted_files(&self, sort_type: SortType) -> Vec<Weak<FileInfo>>;
-}
-
-impl SortedFileInfoCollection for Vec<Rc<FileInfo>> {
-  fn sorted_files(&self, sort_type: SortType) -> Vec<Weak<FileInfo>> {
-    let mut out: Vec<Weak<FileInfo>> = Vec::with_capacity(self.len());
-    
-    self.iter().for_each(|f| out.push(Rc::downgrade(f)));
-    out.sort(sort_type);
-
-    out
-  }
-}
\ No newline at end of file
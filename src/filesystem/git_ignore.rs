@@ -0,0 +1,246 @@
+// Standard Libraries
+use std::collections::HashMap;
+use std::fs::read_to_string;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+// Project Modules
+use super::Glob;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GitIgnoreMode {
+  /// Leave ignored entries out
+  Hide,
+  /// List ignored entries dimmed
+  Dim
+}
+
+/// Line of a .gitignore, .git/info/exclude or .ignore file
+#[derive(Debug)]
+struct IgnoreRule {
+  glob: Glob,
+  /// "!pattern" re-includes what an earlier rule ignored
+  negated: bool,
+  /// "pattern/" only matches directories
+  dir_only: bool,
+  /// Patterns with a '/' before their end match the path relative to the file's
+  /// directory, the others only the name at any depth
+  anchored: bool
+}
+
+impl IgnoreRule {
+  fn parse(line: &str) -> Option<Self> {
+    let mut pattern: &str = line.strip_suffix('\r').unwrap_or(line);
+    if pattern.starts_with('#') {
+      return None;
+    }
+
+    // Trailing spaces are left out, unless escaped
+    while pattern.ends_with(' ') && !pattern.ends_with("\\ ") {
+      pattern = &pattern[..pattern.len() - 1];
+    }
+
+    let negated: bool = pattern.starts_with('!');
+    if negated {
+      pattern = &pattern[1..];
+    }
+    let dir_only: bool = pattern.ends_with('/');
+    if dir_only {
+      pattern = &pattern[..pattern.len() - 1];
+    }
+    if pattern.is_empty() {
+      return None;
+    }
+    let anchored: bool = pattern.contains('/');
+
+    Some(IgnoreRule {
+      glob: Glob::new_path(pattern.strip_prefix('/').unwrap_or(pattern)),
+      negated,
+      dir_only,
+      anchored
+    })
+  }
+
+  /// Whether the rule matches an entry. Its type is only asked for by rules of directories
+  fn matches(&self, rel_path: &str, name: &str, is_dir: &impl Fn() -> bool) -> bool {
+    self.glob.matches(if self.anchored { rel_path } else { name })
+      && (!self.dir_only || is_dir())
+  }
+}
+
+/// Rules of a directory, linked to the ones of its parents up to the repository root
+#[derive(Debug)]
+pub struct IgnoreDir {
+  /// Name of the directory within its parent
+  name: String,
+  /// Rules of this directory in increasing precedence: .git/info/exclude, .gitignore, .ignore
+  rules: Vec<IgnoreRule>,
+  parent: Option<Rc<IgnoreDir>>,
+  /// Whether .gitignore files apply, as the directory is within a git repository
+  in_repository: bool,
+  /// Whether the directory itself is ignored, which can't be undone by its own rules
+  ignored: bool
+}
+
+impl IgnoreDir {
+  /// Whether an entry of this directory is ignored. The deepest directory with a matching
+  /// rule decides, within its rules the last matching one
+  pub fn is_ignored(&self, name: &str, is_dir: impl Fn() -> bool) -> bool {
+    if self.ignored {
+      return true;
+    }
+
+    let mut rel_path: String = name.to_string();
+    let mut level: Option<&IgnoreDir> = Some(self);
+
+    while let Some(dir) = level {
+      if let Some(rule) = dir.rules.iter().rev().find(|rule| rule.matches(&rel_path, name, &is_dir)) {
+        return !rule.negated;
+      }
+      rel_path = format!("{}/{}", dir.name, rel_path);
+      level = dir.parent.as_deref();
+    }
+
+    false
+  }
+}
+
+/// Ignore files read so far, by the canonical path of their directory (--git-ignore)
+#[derive(Debug, Default)]
+pub struct GitIgnore {
+  cache: HashMap<PathBuf, Rc<IgnoreDir>>
+}
+
+impl GitIgnore {
+  pub fn new() -> Self {
+    GitIgnore::default()
+  }
+
+  /// Rules for the entries of a directory, reading the ignore files of it and its parents,
+  /// unless already cached. Outside of git repositories only .ignore files apply
+  pub fn dir(&mut self, dir: &Path) -> Option<Rc<IgnoreDir>> {
+    let dir: PathBuf = dir.canonicalize().ok()?;
+    Some(self.load(&dir))
+  }
+
+  fn load(&mut self, dir: &Path) -> Rc<IgnoreDir> {
+    if let Some(ignore_dir) = self.cache.get(dir) {
+      return ignore_dir.clone();
+    }
+
+    // Repositories are independent of the directories above them
+    let repository_root: bool = dir.join(".git").exists();
+    let parent: Option<Rc<IgnoreDir>> = match dir.parent() {
+      Some(parent) if !repository_root => Some(self.load(parent)),
+      _ => None
+    };
+    let in_repository: bool = repository_root || parent.as_ref().is_some_and(|p| p.in_repository);
+
+    let mut rules: Vec<IgnoreRule> = Vec::new();
+    if repository_root {
+      rules.extend(read_rules(&dir.join(".git/info/exclude")));
+    }
+    if in_repository {
+      rules.extend(read_rules(&dir.join(".gitignore")));
+    }
+    rules.extend(read_rules(&dir.join(".ignore")));
+
+    let name: String = dir.file_name()
+      .map(|name| name.to_string_lossy().into_owned())
+      .unwrap_or_default();
+    let ignored: bool = parent.as_ref().is_some_and(|p| p.is_ignored(&name, || true));
+
+    let ignore_dir: Rc<IgnoreDir> = Rc::new(IgnoreDir { name, rules, parent, in_repository, ignored });
+    self.cache.insert(dir.to_path_buf(), ignore_dir.clone());
+    ignore_dir
+  }
+}
+
+/// Rules of an ignore file, none if it can't be read
+fn read_rules(path: &Path) -> Vec<IgnoreRule> {
+  match read_to_string(path) {
+    Ok(content) => content.lines().filter_map(IgnoreRule::parse).collect(),
+    Err(_) => Vec::new()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{IgnoreDir, IgnoreRule};
+  use std::rc::Rc;
+
+  fn ignore_dir(name: &str, lines: &[&str], parent: Option<Rc<IgnoreDir>>) -> Rc<IgnoreDir> {
+    let ignored: bool = parent.as_ref().is_some_and(|p| p.is_ignored(name, || true));
+    Rc::new(IgnoreDir {
+      name: name.into(),
+      rules: lines.iter().copied().filter_map(IgnoreRule::parse).collect(),
+      parent,
+      in_repository: true,
+      ignored
+    })
+  }
+
+  #[test]
+  fn parse_rules() {
+    assert!(IgnoreRule::parse("# comment").is_none());
+    assert!(IgnoreRule::parse("").is_none());
+    assert!(IgnoreRule::parse("!").is_none());
+
+    let rule: IgnoreRule = IgnoreRule::parse("!build/\r").unwrap();
+    assert!(rule.negated && rule.dir_only && !rule.anchored);
+
+    let rule: IgnoreRule = IgnoreRule::parse("/target").unwrap();
+    assert!(!rule.negated && !rule.dir_only && rule.anchored);
+    assert!(rule.matches("target", "target", &|| false));
+
+    assert!(IgnoreRule::parse("doc/*.html").unwrap().anchored);
+    assert!(IgnoreRule::parse("a ").unwrap().matches("a", "a", &|| false));
+    assert!(IgnoreRule::parse("a\\ ").unwrap().matches("a ", "a ", &|| false));
+  }
+
+  #[test]
+  fn last_matching_rule_decides() {
+    let dir: Rc<IgnoreDir> = ignore_dir("repo", &["*.o", "!keep.o"], None);
+    assert!(dir.is_ignored("main.o", || false));
+    assert!(!dir.is_ignored("keep.o", || false));
+
+    let dir: Rc<IgnoreDir> = ignore_dir("repo", &["!keep.o", "*.o"], None);
+    assert!(dir.is_ignored("keep.o", || false));
+  }
+
+  #[test]
+  fn deeper_directories_take_precedence() {
+    let root: Rc<IgnoreDir> = ignore_dir("repo", &["*.log"], None);
+    let sub: Rc<IgnoreDir> = ignore_dir("sub", &["!debug.log"], Some(root));
+    assert!(!sub.is_ignored("debug.log", || false));
+    assert!(sub.is_ignored("other.log", || false));
+  }
+
+  #[test]
+  fn anchored_rules_match_relative_to_their_directory() {
+    let root: Rc<IgnoreDir> = ignore_dir("repo", &["/out", "doc/*.html"], None);
+    assert!(root.is_ignored("out", || false));
+
+    let sub: Rc<IgnoreDir> = ignore_dir("sub", &[], Some(root.clone()));
+    assert!(!sub.is_ignored("out", || false));
+
+    let doc: Rc<IgnoreDir> = ignore_dir("doc", &[], Some(root));
+    assert!(doc.is_ignored("index.html", || false));
+  }
+
+  #[test]
+  fn dir_only_rules_ask_for_the_type_on_match() {
+    let dir: Rc<IgnoreDir> = ignore_dir("repo", &["build/"], None);
+    assert!(dir.is_ignored("build", || true));
+    assert!(!dir.is_ignored("build", || false));
+    assert!(!dir.is_ignored("src", || panic!("type read for a name no rule matches")));
+  }
+
+  #[test]
+  fn entries_of_excluded_directories_stay_ignored() {
+    let root: Rc<IgnoreDir> = ignore_dir("repo", &["build/", "!build/keep"], None);
+    let build: Rc<IgnoreDir> = ignore_dir("build", &["!keep"], Some(root));
+    assert!(build.is_ignored("keep", || false));
+    assert!(build.is_ignored("other", || false));
+  }
+}
@@ -0,0 +1,397 @@
+/// Named classes within brackets, like "[[:digit:]]"
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CharClass {
+  Alnum,
+  Alpha,
+  Blank,
+  Cntrl,
+  Digit,
+  Graph,
+  Lower,
+  Print,
+  Punct,
+  Space,
+  Upper,
+  Xdigit
+}
+
+impl CharClass {
+  fn from_name(name: &str) -> Option<Self> {
+    match name {
+      "alnum" => Some(CharClass::Alnum),
+      "alpha" => Some(CharClass::Alpha),
+      "blank" => Some(CharClass::Blank),
+      "cntrl" => Some(CharClass::Cntrl),
+      "digit" => Some(CharClass::Digit),
+      "graph" => Some(CharClass::Graph),
+      "lower" => Some(CharClass::Lower),
+      "print" => Some(CharClass::Print),
+      "punct" => Some(CharClass::Punct),
+      "space" => Some(CharClass::Space),
+      "upper" => Some(CharClass::Upper),
+      "xdigit" => Some(CharClass::Xdigit),
+      _ => None
+    }
+  }
+
+  fn contains(&self, c: char) -> bool {
+    match self {
+      CharClass::Alnum => c.is_alphanumeric(),
+      CharClass::Alpha => c.is_alphabetic(),
+      CharClass::Blank => c == ' ' || c == '\t',
+      CharClass::Cntrl => c.is_control(),
+      CharClass::Digit => c.is_ascii_digit(),
+      CharClass::Graph => !c.is_control() && !c.is_whitespace(),
+      CharClass::Lower => c.is_lowercase(),
+      CharClass::Print => !c.is_control(),
+      CharClass::Punct => c.is_ascii_punctuation(),
+      CharClass::Space => c.is_whitespace(),
+      CharClass::Upper => c.is_uppercase(),
+      CharClass::Xdigit => c.is_ascii_hexdigit()
+    }
+  }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ClassItem {
+  Char(char),
+  Range(char, char),
+  Named(CharClass)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+  /// Character, that has to match exactly, also if escaped by '\'
+  Literal(char),
+  /// '?'
+  AnyChar,
+  /// '*'
+  AnyString,
+  /// "**/" of path patterns, any number of leading directories
+  AnyDirs,
+  /// Trailing "/**" of path patterns, everything within the directory
+  AnyPath,
+  /// "[...]", or "[!...]" if negated
+  Class { negated: bool, items: Vec<ClassItem> }
+}
+
+impl Token {
+  fn matches(&self, c: char) -> bool {
+    match self {
+      Token::Literal(literal) => *literal == c,
+      Token::AnyChar | Token::AnyString | Token::AnyDirs | Token::AnyPath => true,
+      Token::Class { negated, items } => {
+        let found: bool = items.iter().any(|item| match item {
+          ClassItem::Char(item) => *item == c,
+          ClassItem::Range(start, end) => (*start..=*end).contains(&c),
+          ClassItem::Named(class) => class.contains(c)
+        });
+        found != *negated
+      }
+    }
+  }
+}
+
+/// Shell pattern, matched like fnmatch(3). By default with FNM_PERIOD: a leading '.' has
+/// to be matched by a '.' in the pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct Glob {
+  tokens: Vec<Token>,
+  /// Whether '*', '?' and brackets stop at '/' (FNM_PATHNAME)
+  pathname: bool,
+  /// Whether a leading '.' needs to be matched explicitly (FNM_PERIOD)
+  period: bool
+}
+
+impl Glob {
+  /// Pattern for file names, like --ignore and --hide
+  pub fn new(pattern: &str) -> Self {
+    Glob { tokens: tokenize(pattern, false), pathname: false, period: true }
+  }
+
+  /// Pattern without flags, for other text than file names, like the TERM lines of dircolors
+  pub fn new_plain(pattern: &str) -> Self {
+    Glob { tokens: tokenize(pattern, false), pathname: false, period: false }
+  }
+
+  /// Pattern for relative paths, like in .gitignore: "**/" matches any number of
+  /// directories, a trailing "/**" everything inside. Leading '.' are not special
+  pub fn new_path(pattern: &str) -> Self {
+    Glob { tokens: tokenize(pattern, true), pathname: true, period: false }
+  }
+
+  pub fn matches(&self, name: &str) -> bool {
+    let chars: Vec<char> = name.chars().collect();
+    let leading_period: bool = self.period && chars.first() == Some(&'.');
+    let mut token_idx: usize = 0;
+    let mut char_idx: usize = 0;
+    // Position of the last '*' and of the first character it doesn't match yet
+    let mut star: Option<(usize, usize)> = None;
+    // Position of the last "**/" and of the first directory it doesn't match yet
+    let mut dirs: Option<(usize, usize)> = None;
+
+    while char_idx < chars.len() {
+      let hidden: bool = char_idx == 0 && leading_period;
+      let c: char = chars[char_idx];
+      // Only literals match a leading '.' or the '/' of a path
+      let literal_only: bool = hidden || (self.pathname && c == '/');
+
+      match self.tokens.get(token_idx) {
+        // No wildcard faces a leading '.', and no '*' came before it to backtrack to
+        Some(Token::AnyString) if hidden => return false,
+        Some(Token::AnyString) => {
+          star = Some((token_idx, char_idx));
+          token_idx += 1;
+          continue;
+        },
+        // Earlier '*' are within directories, that are matched already
+        Some(Token::AnyDirs) => {
+          dirs = Some((token_idx, char_idx));
+          star = None;
+          token_idx += 1;
+          continue;
+        },
+        Some(Token::AnyPath) => return true,
+        Some(token @ Token::Literal(_)) if token.matches(c) => {
+          token_idx += 1;
+          char_idx += 1;
+          continue;
+        },
+        Some(token) if !literal_only && token.matches(c) => {
+          token_idx += 1;
+          char_idx += 1;
+          continue;
+        },
+        _ => ()
+      }
+
+      // Let the last '*' match one more character, which is no '/' of a path
+      if let Some((star_idx, star_char_idx)) = star
+        && !(self.pathname && chars[star_char_idx] == '/') {
+        token_idx = star_idx + 1;
+        char_idx = star_char_idx + 1;
+        star = Some((star_idx, char_idx));
+        continue;
+      }
+
+      // Otherwise let the last "**/" match one more directory
+      let next_dir: Option<(usize, usize)> = dirs.and_then(|(dirs_idx, dirs_char_idx)| {
+        chars[dirs_char_idx..].iter()
+          .position(|c| *c == '/')
+          .map(|slash| (dirs_idx, dirs_char_idx + slash + 1))
+      });
+      match next_dir {
+        Some((dirs_idx, dirs_char_idx)) => {
+          token_idx = dirs_idx + 1;
+          char_idx = dirs_char_idx;
+          dirs = next_dir;
+          star = None;
+        },
+        None => return false
+      }
+    }
+
+    self.tokens[token_idx..].iter()
+      .all(|token| matches!(token, Token::AnyString | Token::AnyDirs | Token::AnyPath))
+  }
+}
+
+fn tokenize(pattern: &str, pathname: bool) -> Vec<Token> {
+  let chars: Vec<char> = pattern.chars().collect();
+  let mut tokens: Vec<Token> = Vec::new();
+  let mut idx: usize = 0;
+
+  while idx < chars.len() {
+    match chars[idx] {
+      // "**" only spans directories as a whole path component, otherwise it is a '*'
+      '*' if pathname && chars.get(idx + 1) == Some(&'*')
+        && (idx == 0 || chars[idx - 1] == '/') => {
+        match chars.get(idx + 2) {
+          Some('/') => {
+            tokens.push(Token::AnyDirs);
+            idx += 2;
+          },
+          None => {
+            tokens.push(Token::AnyPath);
+            idx += 1;
+          },
+          Some(_) => tokens.push(Token::AnyString)
+        }
+      },
+      '*' => tokens.push(Token::AnyString),
+      '?' => tokens.push(Token::AnyChar),
+      // A trailing '\' matches itself
+      '\\' if idx + 1 < chars.len() => {
+        idx += 1;
+        tokens.push(Token::Literal(chars[idx]));
+      },
+      '[' => match parse_class(&chars, idx + 1) {
+        Some((token, end)) => {
+          tokens.push(token);
+          idx = end;
+        },
+        // Without closing ']' it is an ordinary character
+        None => tokens.push(Token::Literal('['))
+      },
+      c => tokens.push(Token::Literal(c))
+    }
+    idx += 1;
+  }
+
+  tokens
+}
+
+/// Parses a bracket expression after its '['. Returns the token and the index of the closing ']'
+fn parse_class(chars: &[char], start: usize) -> Option<(Token, usize)> {
+  let mut idx: usize = start;
+  let negated: bool = matches!(chars.get(idx), Some('!') | Some('^'));
+  if negated {
+    idx += 1;
+  }
+
+  let mut items: Vec<ClassItem> = Vec::new();
+  let first: usize = idx;
+
+  loop {
+    let c: char = *chars.get(idx)?;
+
+    // ']' right after '[' or "[!" is part of the class
+    if c == ']' && idx > first {
+      return Some((Token::Class { negated, items }, idx));
+    }
+
+    if c == '[' && chars.get(idx + 1) == Some(&':') {
+      let name_end: Option<usize> = (idx + 2..chars.len().saturating_sub(1))
+        .find(|&end| chars[end] == ':' && chars[end + 1] == ']');
+      if let Some(name_end) = name_end {
+        let name: String = chars[idx + 2..name_end].iter().collect();
+        // Unknown names make the '[' an ordinary character
+        items.push(ClassItem::Named(CharClass::from_name(&name)?));
+        idx = name_end + 2;
+        continue;
+      }
+    }
+
+    let (c, next): (char, usize) = {
+      if c == '\\' { (*chars.get(idx + 1)?, idx + 2) }
+      else { (c, idx + 1) }
+    };
+
+    // "a-z", while a '-' in front of the closing ']' is a literal
+    if chars.get(next) == Some(&'-') && chars.get(next + 1).is_some_and(|end| *end != ']') {
+      let (end, after): (char, usize) = match chars[next + 1] {
+        '\\' => (*chars.get(next + 2)?, next + 3),
+        end => (end, next + 2)
+      };
+      items.push(ClassItem::Range(c, end));
+      idx = after;
+    } else {
+      items.push(ClassItem::Char(c));
+      idx = next;
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::Glob;
+
+  #[test]
+  fn wildcards_match_any_characters() {
+    assert!(Glob::new("*.rs").matches("main.rs"));
+    assert!(Glob::new("*").matches(""));
+    assert!(!Glob::new("*.rs").matches("main.rsx"));
+    assert!(Glob::new("a?c").matches("abc"));
+    assert!(!Glob::new("a?c").matches("ac"));
+    assert!(Glob::new("*a*b").matches("xaxxbab"));
+  }
+
+  #[test]
+  fn brackets_match_sets_and_ranges() {
+    assert!(Glob::new("[abc]").matches("b"));
+    assert!(!Glob::new("[abc]").matches("d"));
+    assert!(Glob::new("[a-c]x").matches("bx"));
+    assert!(!Glob::new("[!a-c]").matches("b"));
+    assert!(Glob::new("[^a-c]").matches("d"));
+    assert!(Glob::new("[]]").matches("]"));
+    assert!(Glob::new("[!]]").matches("a"));
+    assert!(Glob::new("[a-]").matches("-"));
+    // Without closing ']' the '[' is literal
+    assert!(Glob::new("[ab").matches("[ab"));
+  }
+
+  #[test]
+  fn brackets_match_named_classes() {
+    assert!(Glob::new("[[:digit:]][[:alpha:]]").matches("1a"));
+    assert!(!Glob::new("[[:digit:]]").matches("a"));
+    assert!(Glob::new("[[:upper:][:space:]]").matches(" "));
+    assert!(Glob::new("[![:xdigit:]]").matches("g"));
+  }
+
+  #[test]
+  fn backslash_escapes() {
+    assert!(Glob::new("\\*").matches("*"));
+    assert!(!Glob::new("\\*").matches("a"));
+    assert!(Glob::new("[\\]]").matches("]"));
+    assert!(Glob::new("a\\").matches("a\\"));
+  }
+
+  #[test]
+  fn leading_periods_need_a_period() {
+    assert!(!Glob::new("*").matches(".hidden"));
+    assert!(!Glob::new("*.txt").matches(".txt"));
+    assert!(!Glob::new("*txt").matches(".txt"));
+    assert!(!Glob::new("?hidden").matches(".hidden"));
+    assert!(!Glob::new("[.]hidden").matches(".hidden"));
+    assert!(Glob::new(".*").matches(".hidden"));
+    assert!(Glob::new(".*.txt").matches(".a.txt"));
+    assert!(Glob::new("a*").matches("a.b"));
+    assert!(Glob::new_plain("*").matches(".hidden"));
+    assert!(Glob::new_plain("*.txt").matches(".txt"));
+    assert!(Glob::new_path("*").matches(".hidden"));
+  }
+
+  #[test]
+  fn stars_backtrack_in_linear_time() {
+    let name: String = "a".repeat(60);
+    assert!(!Glob::new("*a*a*a*a*a*a*a*a*b").matches(&name));
+    assert!(Glob::new("*a*a*a*a*a*a*a*a*").matches(&name));
+    assert!(!Glob::new_path("**/*a*a*a*a*a*a*a*a*b").matches(&format!("{0}/{0}/{0}", name)));
+  }
+
+  #[test]
+  fn path_stars_stop_at_slashes() {
+    assert!(Glob::new_path("a*b").matches("axxb"));
+    assert!(!Glob::new_path("a*b").matches("ax/xb"));
+    assert!(!Glob::new_path("a?b").matches("a/b"));
+    assert!(!Glob::new_path("a[/]b").matches("a/b"));
+    assert!(Glob::new_path("*/b").matches("a/b"));
+    assert!(Glob::new("a*b").matches("ax/xb"));
+  }
+
+  #[test]
+  fn any_dirs_match_whole_directories() {
+    let glob: Glob = Glob::new_path("**/a*b");
+    assert!(glob.matches("ab"));
+    assert!(glob.matches("x/y/axb"));
+    assert!(glob.matches("ax/ab"));
+    assert!(!glob.matches("x/ya/b"));
+    assert!(!glob.matches("xab"));
+
+    let glob: Glob = Glob::new_path("a/**/b");
+    assert!(glob.matches("a/b"));
+    assert!(glob.matches("a/x/y/b"));
+    assert!(!glob.matches("a/xb"));
+    assert!(!glob.matches("ab"));
+  }
+
+  #[test]
+  fn any_path_matches_everything_inside() {
+    let glob: Glob = Glob::new_path("a/**");
+    assert!(glob.matches("a/b"));
+    assert!(glob.matches("a/b/c"));
+    assert!(!glob.matches("a"));
+    assert!(!glob.matches("ab/c"));
+    assert!(Glob::new_path("**").matches("a/b"));
+  }
+}
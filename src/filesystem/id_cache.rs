@@ -0,0 +1,51 @@
+// Standard Libraries
+use std::collections::HashMap;
+use std::fs::read_to_string;
+
+/// Names of users and groups, read once per run from /etc/passwd and /etc/group
+#[derive(Debug, Default)]
+pub struct IdCache {
+  users: HashMap<u32, String>,
+  groups: HashMap<u32, String>
+}
+
+impl IdCache {
+  /// Empty cache, every lookup falls back to the numeric ID
+  pub fn new() -> Self {
+    IdCache::default()
+  }
+
+  pub fn load() -> Self {
+    IdCache {
+      users: Self::parse_database("/etc/passwd"),
+      groups: Self::parse_database("/etc/group")
+    }
+  }
+
+  /// Parses "name:password:ID:..." lines. The first entry of an ID wins, like with getpwuid()
+  fn parse_database(path: &str) -> HashMap<u32, String> {
+    let mut out: HashMap<u32, String> = HashMap::new();
+    let content: String = read_to_string(path).unwrap_or_default();
+
+    for line in content.lines() {
+      let mut fields = line.split(':');
+      let (name, id) = match (fields.next(), fields.nth(1)) {
+        (Some(name), Some(id)) => (name, id),
+        _ => continue
+      };
+      if let Ok(id) = id.parse::<u32>() {
+        out.entry(id).or_insert_with(|| name.to_string());
+      }
+    }
+
+    out
+  }
+
+  pub fn user_name(&self, uid: u32) -> Option<&str> {
+    self.users.get(&uid).map(|s| s.as_str())
+  }
+
+  pub fn group_name(&self, gid: u32) -> Option<&str> {
+    self.groups.get(&gid).map(|s| s.as_str())
+  }
+}
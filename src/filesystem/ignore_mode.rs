@@ -1,3 +1,5 @@
+// Named like the ignore modes of GNU ls
+#[allow(clippy::enum_variant_names)]
 #[derive(Default, PartialEq, Debug)]
 pub enum IgnoreMode {
   /// Ignore files whose names start with '.'
@@ -0,0 +1,150 @@
+// Standard Libraries
+use std::collections::HashSet;
+use std::fs::{read_link, Metadata};
+use std::io::ErrorKind;
+use std::os::linux::fs::MetadataExt;
+use std::path::{Component, Path, PathBuf};
+
+// Cargo Modules
+use libc::ELOOP;
+
+// Project Modules
+use super::{link_target_path, DevIno};
+
+/// Hops the kernel follows before giving up with ELOOP (SYMLOOP_MAX on Linux)
+const MAX_HOPS: usize = 40;
+
+#[derive(Debug)]
+pub struct LinkHop {
+  /// Target as stored in the link
+  pub target: String,
+  /// Whether the target is an absolute path
+  pub absolute: bool
+}
+
+#[derive(Debug, PartialEq)]
+pub enum ChainEnd {
+  /// The last hop is no symbolic link
+  Resolved,
+  /// The chain leads back to one of its links, or is longer than the kernel follows
+  Loop,
+  /// First component of the last hop, that doesn't exist
+  Missing(String),
+  /// A link or path component could not be read
+  Unreadable(String)
+}
+
+/// Every hop of a symbolic link (--link-chain)
+#[derive(Debug)]
+pub struct LinkChain {
+  pub hops: Vec<LinkHop>,
+  pub end: ChainEnd
+}
+
+impl LinkChain {
+  /// Follows the link at link_path hop by hop
+  pub fn resolve(link_path: &str) -> Self {
+    let mut hops: Vec<LinkHop> = Vec::new();
+    let mut visited: HashSet<DevIno> = HashSet::new();
+    let mut current: PathBuf = PathBuf::from(link_path);
+
+    if let Ok(metadata) = current.symlink_metadata() {
+      visited.insert(DevIno::new(metadata.st_dev(), metadata.st_ino()));
+    }
+
+    let end: ChainEnd = loop {
+      let target: PathBuf = match read_link(&current) {
+        Ok(target) => target,
+        Err(e) => break ChainEnd::Unreadable(e.to_string())
+      };
+      let next: PathBuf = link_target_path(&current.to_string_lossy(), &target);
+      hops.push(LinkHop {
+        target: target.to_string_lossy().into_owned(),
+        absolute: target.is_absolute()
+      });
+
+      let metadata: Metadata = match next.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(e) if e.kind() == ErrorKind::NotFound => break ChainEnd::Missing(first_missing_component(&next)),
+        Err(e) if e.raw_os_error() == Some(ELOOP) => break ChainEnd::Loop,
+        Err(e) => break ChainEnd::Unreadable(e.to_string())
+      };
+
+      if !metadata.file_type().is_symlink() {
+        break ChainEnd::Resolved;
+      }
+      if hops.len() >= MAX_HOPS || !visited.insert(DevIno::new(metadata.st_dev(), metadata.st_ino())) {
+        break ChainEnd::Loop;
+      }
+      current = next;
+    };
+
+    LinkChain { hops, end }
+  }
+}
+
+/// Shortest leading part of the path, that doesn't exist. Links within it are followed
+fn first_missing_component(path: &Path) -> String {
+  let mut prefix: PathBuf = PathBuf::new();
+
+  // Entries of "." are joined to it, which is left out
+  for component in path.components().skip_while(|c| *c == Component::CurDir) {
+    prefix.push(component);
+    if prefix.metadata().is_err_and(|e| e.kind() == ErrorKind::NotFound) {
+      break;
+    }
+  }
+
+  prefix.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ChainEnd, LinkChain};
+  use crate::filesystem::test_dir::TestDir;
+  use std::fs::{create_dir, File};
+  use std::os::unix::fs::symlink;
+
+  /// Resolves a link of the directory
+  fn resolve(dir: &TestDir, name: &str) -> LinkChain {
+    LinkChain::resolve(dir.join(name).to_str().unwrap())
+  }
+
+  #[test]
+  fn follows_every_hop() {
+    let dir: TestDir = TestDir::new("link-chain-hops");
+    File::create(dir.join("file")).unwrap();
+    symlink("file", dir.join("b")).unwrap();
+    symlink(dir.join("b"), dir.join("a")).unwrap();
+
+    let chain: LinkChain = resolve(&dir, "a");
+    assert_eq!(chain.end, ChainEnd::Resolved);
+    assert_eq!(chain.hops.len(), 2);
+    assert!(chain.hops[0].absolute);
+    assert_eq!(chain.hops[1].target, "file");
+    assert!(!chain.hops[1].absolute);
+  }
+
+  #[test]
+  fn detects_loops() {
+    let dir: TestDir = TestDir::new("link-chain-loop");
+    symlink("b", dir.join("a")).unwrap();
+    symlink("a", dir.join("b")).unwrap();
+    symlink("self", dir.join("self")).unwrap();
+
+    let chain: LinkChain = resolve(&dir, "a");
+    assert_eq!(chain.end, ChainEnd::Loop);
+    assert_eq!(chain.hops.len(), 2);
+    assert_eq!(resolve(&dir, "self").end, ChainEnd::Loop);
+  }
+
+  #[test]
+  fn names_the_missing_component() {
+    let dir: TestDir = TestDir::new("link-chain-missing");
+    create_dir(dir.join("sub")).unwrap();
+    symlink("sub/gone/file", dir.join("a")).unwrap();
+
+    let missing: String = dir.join("sub/gone").to_string_lossy().into_owned();
+    assert_eq!(resolve(&dir, "a").end, ChainEnd::Missing(missing));
+  }
+}
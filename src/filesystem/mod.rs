@@ -1,29 +1,57 @@
 mod error;
+mod dereference;
 mod dev_ino;
 mod fileinfo;
+mod git_ignore;
+mod glob;
+mod id_cache;
+mod link_chain;
 mod loop_manager;
+mod name_style;
 mod pending;
+mod quoting;
+mod sort;
+mod time_type;
 pub mod ignore_mode;
+#[cfg(test)]
+pub mod test_dir;
 
 // Standard Libraries
+use std::cell::OnceCell;
 use std::ffi::OsString;
+use std::io::ErrorKind;
 use std::fs::{metadata, read_dir, read_link}; 
 use std::os::linux::fs::MetadataExt;
 use std::os::unix::fs::DirEntryExt;
 pub use std::rc::Rc;
 pub use std::fs::{Metadata, ReadDir, DirEntry};
-pub use std::path::PathBuf;
+pub use std::path::{Path, PathBuf};
+
+// Cargo Modules
+use unicode_width::UnicodeWidthStr;
 
 // Project Modules 
 use super::{ExitStatus, IntoExitStatus};
 use super::math::*;
 use super::working_set::*;
-use super::print::format_info::*;
+use super::print::{format_info::*, format::*};
 use self::dev_ino::*;
+pub use self::dereference::*;
 pub use self::error::*;
 pub use self::pending::*;
 pub use self::fileinfo::*;
+pub use self::git_ignore::*;
+pub use self::glob::*;
+pub use self::id_cache::*;
+pub use self::link_chain::*;
 pub use self::loop_manager::*;
+pub use self::name_style::*;
+pub use self::quoting::*;
+pub use self::sort::*;
+pub use self::time_type::*;
+
+/// Size of the units of st_blocks
+pub const ST_NBLOCKSIZE: u64 = 512;
 
 pub fn open_dir(path: &PathBuf) -> Result<ReadDir> {
   read_dir(path).map_err(
@@ -47,12 +75,12 @@ pub fn file_name_to_string(pending: &Pending, file_name: OsString) -> Result<Str
   }
 }
 
-pub fn pathname_to_string(path: &PathBuf) -> Result<String> {
+pub fn pathname_to_string(path: &Path) -> Result<String> {
   match path.to_str() {
     Some(s) => Ok(s.to_string()),
     None => Err(
       Error::PathInvalidUTF8Error { 
-        path: path.clone() 
+        path: path.to_path_buf() 
       }
     )
   }
@@ -60,10 +88,25 @@ pub fn pathname_to_string(path: &PathBuf) -> Result<String> {
 
 /// Resolves a relative path. Assumes directory is Some
 fn resolve_path(rel_path: PathBuf, directory: Option<&PathBuf>) -> PathBuf {
-  if directory.is_some() {
-    directory.unwrap().join(rel_path)
-  } else {
-    rel_path
+  match directory {
+    Some(directory) => directory.join(rel_path),
+    None => rel_path
+  }
+}
+
+/// Relative link targets start from the directory containing the link
+fn link_target_path(link_path: &str, target: &Path) -> PathBuf {
+  match Path::new(link_path).parent() {
+    Some(parent) if target.is_relative() => parent.join(target),
+    _ => target.to_path_buf()
+  }
+}
+
+/// Type of a directory entry, as far as the directory tells it
+fn entry_file_type(dir_entry: &DirEntry) -> FileType {
+  match dir_entry.file_type() {
+    Ok(file_type) => FileType::new(file_type, false),
+    Err(_) => FileType::Unknown
   }
 }
 
@@ -92,9 +135,8 @@ pub fn file_name_concat(dirname: &str, filename: &str) -> String {
 
 pub fn gobble_file (working_set: &mut WorkingSet, name: &str, mut file_type: FileType, 
                     inode: u64, cli_arg: bool, dir_name: Option<&PathBuf>) -> u64 {
-  let blocks: u64; 
   let original_path = PathBuf::from(name);
-  let mut resolved_path: PathBuf;
+  let resolved_path: PathBuf;
   let mut full_name: String = name.to_string();
   let mut link_name: Option<String> = None;
   let metadata: Metadata; 
@@ -102,11 +144,15 @@ pub fn gobble_file (working_set: &mut WorkingSet, name: &str, mut file_type: Fil
 
   debug_assert!(! cli_arg || inode == 0);
 
+  // Entries ignored by git are dimmed, if not hidden (--git-ignore=dim)
+  let dimmed: bool = !cli_arg && working_set.git_ignore_mode == Some(GitIgnoreMode::Dim)
+    && working_set.git_ignored(name, || file_type == FileType::Directory);
+
   // Technically stat always needs to be checked, due to color properties always being enabled
   let check_stat = cli_arg 
-    || working_set.args.long
+    || working_set.format == Format::Long
     || matches!(file_type, FileType::Unknown | FileType::Directory | FileType::Normal)   
-    || (matches!(file_type, FileType::SymbolicLink | FileType::Unknown) && working_set.args.dereference); 
+    || (matches!(file_type, FileType::SymbolicLink | FileType::Unknown) && working_set.dereference == Dereference::Always); 
 
   if check_stat || original_path.is_relative() && dir_name.is_some() {
     resolved_path = resolve_path(original_path.clone(), dir_name);
@@ -121,22 +167,27 @@ pub fn gobble_file (working_set: &mut WorkingSet, name: &str, mut file_type: Fil
     resolved_path = original_path.clone();
   }
 
-  let metadata_result: Result<Metadata>;
-  if working_set.args.dereference {
-    metadata_result = resolved_path.metadata().map_err(
-      |e| Error::CannotAccessFileError {
-        name: full_name.clone(), 
-        message: e.to_string() 
-      }
-    );
-  } else {
-    metadata_result = resolved_path.symlink_metadata().map_err(
-      |e| Error::CannotAccessFileError {
-        name: full_name.clone(), 
-        message: e.to_string()
-      }
-    );
+  let dereference: Dereference = working_set.dereference;
+  let mut stat_result: std::io::Result<Metadata> = {
+    if dereference.follows(cli_arg) { resolved_path.metadata() }
+    else { resolved_path.symlink_metadata() }
+  };
+  // Links to anything but directories are shown themselves, as are dangling ones
+  if cli_arg && dereference == Dereference::CommandLineSymlinkToDir {
+    let need_lstat: bool = match &stat_result {
+      Ok(m) => !m.is_dir(),
+      Err(e) => e.kind() == ErrorKind::NotFound
+    };
+    if need_lstat {
+      stat_result = resolved_path.symlink_metadata();
+    }
   }
+  let metadata_result: Result<Metadata> = stat_result.map_err(
+    |e| Error::CannotAccessFileError {
+      name: full_name.clone(), 
+      message: e.to_string()
+    }
+  );
   match metadata_result { 
     Ok(m) => metadata = m, 
     Err(e) => {
@@ -144,44 +195,31 @@ pub fn gobble_file (working_set: &mut WorkingSet, name: &str, mut file_type: Fil
       if cli_arg { 
         return 0
       } else {
-        working_set.cwd_files.push(Rc::new(FileInfo::new(
+        let mut file_info = FileInfo::new(
           name.to_string(), 
           None,
           None, 
           None, 
           inode, 
-          file_type)));
+          file_type,
+          working_set.name_style);
+        file_info.dimmed = dimmed;
+        working_set.cwd_files.push(Rc::new(file_info));
         return 0;
       }
     }
   }
 
-  file_type = FileType::determine(&metadata, cli_arg);
+  // With -d, directories on the command line are listed like any other file
+  file_type = FileType::determine(&metadata, cli_arg && !working_set.args.directory);
 
-  if file_type == FileType::SymbolicLink && working_set.args.long {
+  if file_type == FileType::SymbolicLink 
+    && (working_set.format == Format::Long || working_set.style.needs_link_target()) {
     match read_link(&full_name) {
       Ok(target_path) => {
-        let dir_name = PathBuf::from(dir_name.unwrap_or(&PathBuf::from(".")));
-        let abs_target = dir_name.join(&target_path);
-        
-        match abs_target.metadata() {
-          Ok(m) => {
-            resolved_path = target_path;
-            link_metadata = Some(m);
-            link_name = Some(pathname_to_string(&resolved_path).unwrap_or_default());
-          }
-          Err(e) => {
-            resolved_path = target_path;
-            link_name = Some(pathname_to_string(&resolved_path).unwrap_or_default());
-            working_set.exit_status.update(
-            Error::CannotReadSymbolicLinkError {
-              path: resolved_path,
-              message: e.to_string()
-            }.into(),
-            cli_arg
-          );
-          }
-        }
+        // Dangling links are no error, they are only shown as such (or, mi)
+        link_metadata = link_target_path(&full_name, &target_path).metadata().ok();
+        link_name = Some(pathname_to_string(&target_path).unwrap_or_default());
       }
       Err(e) => {
         working_set.exit_status.update(
@@ -195,19 +233,41 @@ pub fn gobble_file (working_set: &mut WorkingSet, name: &str, mut file_type: Fil
     }
   } 
 
-  // Standard Blocksize is 512 bytes
-  blocks = metadata.st_blocks();
+  // Standard Blocksize is ST_NBLOCKSIZE
+  let blocks: u64 = metadata.st_blocks();
+  // Command line arguments are only known by their path
+  let inode: u64 = metadata.st_ino();
+
+  if working_set.print_inode {
+    let info: &mut FormatInfo = working_set.format_info.as_mut().unwrap();
+    info.update_inode_length(digit_width(inode));
+  }
+
+  if working_set.print_block_size {
+    let block_string: String = working_set.block_size.human_readable(blocks, ST_NBLOCKSIZE);
+    let info: &mut FormatInfo = working_set.format_info.as_mut().unwrap();
+    info.update_block_length(UnicodeWidthStr::width(block_string.as_str()));
+  }
 
-  if working_set.args.long {
+  if working_set.format == Format::Long {
     let info: &mut FormatInfo = working_set.format_info.as_mut().unwrap();
     
     info.update_hard_link_length(digit_width(metadata.st_nlink()));
 
-    info.update_user_length(digit_width(metadata.st_uid() as u64));
-    info.update_group_length(digit_width(metadata.st_gid() as u64));
-    // Not necessary for pure "-l"-Argument 
-    //info.update_block_length(digit_count(blocks));
-    
+    // The author is the owner on Linux, so both share the width
+    let id_cache: &IdCache = &working_set.id_cache;
+    if working_set.print_owner || working_set.print_author {
+      info.update_user_length(match id_cache.user_name(metadata.st_uid()) {
+        Some(name) => UnicodeWidthStr::width(name),
+        None => digit_width(metadata.st_uid() as u64)
+      });
+    }
+    if working_set.print_group {
+      info.update_group_length(match id_cache.group_name(metadata.st_gid()) {
+        Some(name) => UnicodeWidthStr::width(name),
+        None => digit_width(metadata.st_gid() as u64)
+      });
+    }
     // Char- and block devices don't have block sizes, but major- and minor dev-numbers instead
     if matches!(file_type, FileType::CharDev | FileType::BlockDev) {
       let st_rdev = metadata.st_rdev();
@@ -218,19 +278,25 @@ pub fn gobble_file (working_set: &mut WorkingSet, name: &str, mut file_type: Fil
       info.update_file_size_lengths(maj_len, min_len);
     }
     else {
-      info.update_file_size_length(digit_width(metadata.st_size()));
+      let size: String = working_set.file_block_size.human_readable(metadata.st_size(), 1);
+      info.update_file_size_length(UnicodeWidthStr::width(size.as_str()));
     }
   }
 
-  // inode won't be printed in pure -l or -C
-  let file_info = FileInfo::new(
+  let mut file_info = FileInfo::new(
     name.to_string(), 
     link_name, 
     Some(metadata), 
     link_metadata,
     inode, 
-    file_type
+    file_type,
+    working_set.name_style
   );
+  file_info.dimmed = dimmed;
+  // Link targets are only shown in long format
+  if working_set.args.link_chain && working_set.format == Format::Long && file_info.link_name.is_some() {
+    file_info.link_chain = Some(LinkChain::resolve(&full_name));
+  }
   working_set.cwd_files.push(Rc::new(file_info));
 
   blocks
@@ -248,7 +314,7 @@ pub fn get_major_and_minor(dev: u64) -> (u64, u64) {
   (major(dev), minor(dev))
 }
 
-pub fn print_dir(working_set: &mut WorkingSet, this_pend: &Box<Pending>, print_dir_name: bool, first: bool) { 
+pub fn print_dir(working_set: &mut WorkingSet, this_pend: &Pending, print_dir_name: bool, first: bool) { 
   let dir: ReadDir;
   let mut dir_entry: DirEntry;
   let mut total_blocks: u64 = 0;
@@ -294,13 +360,26 @@ pub fn print_dir(working_set: &mut WorkingSet, this_pend: &Box<Pending>, print_d
   if working_set.args.recursive || print_dir_name {
     if !first { println!(); }
 
+    let name_style: NameStyle = working_set.name_style;
     match &this_pend.real_name {
-      Some(real_name) => print!("{}", real_name),
-      None => print!("{}", this_pend.get_name())
+      Some(real_name) => print!("{}", name_style.quote(real_name)),
+      None => print!("{}", name_style.quote(this_pend.get_name()))
     }; 
     println!(":");
   } 
 
+  if working_set.git_ignore_mode.is_some() {
+    working_set.enter_git_ignore_dir(&path);
+  }
+
+  // std::fs::ReadDir skips "." and "..", so they are stat'ed through the directory itself.
+  // The kernel resolves ".." across mount points and at "/"
+  for dot_name in [".", ".."] {
+    if !working_set.file_ignored(dot_name) {
+      total_blocks += gobble_file(working_set, dot_name, FileType::Directory, 0, false, Some(&path));
+    }
+  }
+
   // Process directory entries
   for res in dir {
     match res {
@@ -316,7 +395,7 @@ pub fn print_dir(working_set: &mut WorkingSet, this_pend: &Box<Pending>, print_d
       }
     }
     
-    let file_name = match file_name_to_string(&this_pend, dir_entry.file_name()) {
+    let file_name = match file_name_to_string(this_pend, dir_entry.file_name()) {
       Ok(name) => name, 
       Err(e) => { 
         working_set.exit_status.update(e.into(), this_pend.cli_arg); 
@@ -324,28 +403,35 @@ pub fn print_dir(working_set: &mut WorkingSet, this_pend: &Box<Pending>, print_d
       }
     }; 
 
-    if ! working_set.file_ignored(&file_name) {
-      let file_type: FileType = match dir_entry.file_type() {
-        Ok(file_type) => FileType::new(file_type, false),
-        Err(_) => FileType::Unknown
-      };
-      
-      total_blocks += gobble_file(working_set, &file_name, file_type, dir_entry.ino(), false, Some(&path));
-    } 
+    if working_set.file_ignored(&file_name) {
+      continue;
+    }
+
+    // Reading the type stats the entry on file systems without d_type, therefore
+    // it is only read ahead for git ignore rules of directories
+    let file_type: OnceCell<FileType> = OnceCell::new();
+    if working_set.git_ignore_mode == Some(GitIgnoreMode::Hide)
+      && working_set.git_ignored(&file_name, || *file_type.get_or_init(|| entry_file_type(&dir_entry)) == FileType::Directory) {
+      continue;
+    }
+    let file_type: FileType = file_type.into_inner().unwrap_or_else(|| entry_file_type(&dir_entry));
+
+    total_blocks += gobble_file(working_set, &file_name, file_type, dir_entry.ino(), false, Some(&path));
   }
   // Directory closes automatically 
 
+  working_set.warn_lookalike_names();
   working_set.sort_files();
 
   if working_set.args.recursive {
       working_set.extract_dirs_from_files(this_pend.name.as_deref(), false);
   }
   
-  if working_set.args.long {
-    println!("total {}", total_blocks);
+  if working_set.format == Format::Long || working_set.print_block_size {
+    println!("total {}", working_set.block_size.human_readable(total_blocks, ST_NBLOCKSIZE));
   }
   
-  if working_set.sorted_files.len() > 0 {
+  if !working_set.sorted_files.is_empty() {
     super::print::print_current_files(working_set);
   }
 }
\ No newline at end of file
@@ -0,0 +1,161 @@
+// Standard Libraries
+use std::fs::Metadata;
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+
+// Cargo Modules
+use unicode_security::skeleton;
+
+// Project Modules
+use super::{FileType, QuotingStyle};
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum IndicatorStyle {
+  /// No indicators
+  #[default]
+  None = 0,
+
+  /// '/' after directories (-p)
+  Slash,
+
+  /// '/', '@', '|' and '=' after directories, links, FIFOs and sockets (--file-type)
+  FileType,
+
+  /// Like FileType, plus '*' after executables (-F)
+  Classify
+}
+
+impl IndicatorStyle {
+  /// Indicator of a file of the given metadata
+  pub fn indicator(&self, metadata: &Metadata) -> Option<char> {
+    let file_type: std::fs::FileType = metadata.file_type();
+
+    if file_type.is_dir() {
+      return if *self == IndicatorStyle::None { None } else { Some('/') };
+    }
+    if *self == IndicatorStyle::None || *self == IndicatorStyle::Slash {
+      return None;
+    }
+
+    if file_type.is_symlink() { Some('@') }
+    else if file_type.is_fifo() { Some('|') }
+    else if file_type.is_socket() { Some('=') }
+    else if file_type.is_file() && *self == IndicatorStyle::Classify
+      && metadata.permissions().mode() & 0o111 != 0 { Some('*') }
+    else { None }
+  }
+
+  /// Indicator of a file that could not be stat'ed, based on the type read from the directory
+  pub fn indicator_for_type(&self, file_type: &FileType) -> Option<char> {
+    match (self, file_type) {
+      (IndicatorStyle::None, _) => None,
+      (_, FileType::Directory | FileType::ArgDirectory) => Some('/'),
+      (IndicatorStyle::Slash, _) => None,
+      (_, FileType::SymbolicLink) => Some('@'),
+      (_, FileType::FIFO) => Some('|'),
+      (_, FileType::Sock) => Some('='),
+      _ => None
+    }
+  }
+}
+
+/// How file names are written
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct NameStyle {
+  pub quoting_style: QuotingStyle,
+  pub indicator_style: IndicatorStyle,
+  /// Print '?' instead of nonprintable characters (-q)
+  pub hide_control_chars: bool,
+  /// Print bidi controls and invisible characters as <U+XXXX> (--reveal-invisible)
+  pub reveal_invisible: bool
+}
+
+/// Characters that change the display order of a name or are not displayed at all
+pub fn is_invisible(c: char) -> bool {
+  matches!(c,
+    // Bidi controls
+    '\u{061C}' | '\u{200E}' | '\u{200F}' | '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}'
+    // Zero width characters, joiners and invisible operators
+    | '\u{00AD}' | '\u{034F}' | '\u{180E}' | '\u{200B}'..='\u{200D}' | '\u{2060}'..='\u{2064}' | '\u{FEFF}'
+    // Fillers, that look like nothing
+    | '\u{115F}' | '\u{1160}' | '\u{3164}' | '\u{FFA0}'
+    // Tags
+    | '\u{E0000}'..='\u{E007F}'
+  )
+}
+
+/// The UTS #39 skeleton of a name without its invisible characters, equal for names that
+/// look identical: confusable characters like Latin and Cyrillic letters are mapped to the
+/// same prototype, composed and decomposed accents are normalized
+pub fn visible_skeleton(name: &str) -> String {
+  let visible: String = name.chars().filter(|c| !is_invisible(*c)).collect();
+  skeleton(&visible).collect()
+}
+
+impl NameStyle {
+  /// Writes the name as it is output, so that it can't send control sequences to the terminal
+  pub fn quote(&self, name: &str) -> String {
+    let mut quoted: String = self.quoting_style.quote(name);
+
+    if self.reveal_invisible && quoted.chars().any(is_invisible) {
+      quoted = quoted.chars().map(|c| {
+        if is_invisible(c) { format!("<U+{:04X}>", c as u32) } else { c.to_string() }
+      }).collect();
+    }
+
+    if self.hide_control_chars {
+      quoted.chars().map(|c| if c.is_control() { '?' } else { c }).collect()
+    } else {
+      quoted
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{visible_skeleton, FileType, IndicatorStyle};
+  use crate::filesystem::test_dir::TestDir;
+  use std::fs::{metadata, set_permissions, symlink_metadata, File, Permissions};
+  use std::os::unix::fs::{symlink, PermissionsExt};
+
+  #[test]
+  fn indicators_by_style() {
+    let dir: TestDir = TestDir::new("indicators");
+    File::create(dir.join("file")).unwrap();
+    File::create(dir.join("exec")).unwrap();
+    set_permissions(dir.join("exec"), Permissions::from_mode(0o755)).unwrap();
+    symlink("file", dir.join("link")).unwrap();
+
+    let indicators = |style: IndicatorStyle| -> Vec<Option<char>> {
+      vec![
+        style.indicator(&metadata(dir.path()).unwrap()),
+        style.indicator(&metadata(dir.join("file")).unwrap()),
+        style.indicator(&metadata(dir.join("exec")).unwrap()),
+        style.indicator(&symlink_metadata(dir.join("link")).unwrap())
+      ]
+    };
+    assert_eq!(indicators(IndicatorStyle::None), [None, None, None, None]);
+    assert_eq!(indicators(IndicatorStyle::Slash), [Some('/'), None, None, None]);
+    assert_eq!(indicators(IndicatorStyle::FileType), [Some('/'), None, None, Some('@')]);
+    assert_eq!(indicators(IndicatorStyle::Classify), [Some('/'), None, Some('*'), Some('@')]);
+  }
+
+  #[test]
+  fn indicators_by_type_without_metadata() {
+    assert_eq!(IndicatorStyle::Slash.indicator_for_type(&FileType::Directory), Some('/'));
+    assert_eq!(IndicatorStyle::Slash.indicator_for_type(&FileType::FIFO), None);
+    assert_eq!(IndicatorStyle::FileType.indicator_for_type(&FileType::Sock), Some('='));
+    assert_eq!(IndicatorStyle::Classify.indicator_for_type(&FileType::Normal), None);
+    assert_eq!(IndicatorStyle::None.indicator_for_type(&FileType::Directory), None);
+  }
+
+  #[test]
+  fn skeletons_of_lookalike_names_are_equal() {
+    assert_eq!(visible_skeleton("a\u{200B}b"), visible_skeleton("ab"));
+    assert_eq!(visible_skeleton("\u{202E}abc"), visible_skeleton("abc"));
+    assert_eq!(visible_skeleton("cafe\u{0301}"), visible_skeleton("caf\u{00E9}"));
+    assert_eq!(visible_skeleton("p\u{0430}ypal"), visible_skeleton("paypal"));
+    assert_eq!(visible_skeleton("\u{0391}lpha"), visible_skeleton("Alpha"));
+    assert_ne!(visible_skeleton("cafe"), visible_skeleton("caf\u{00E9}"));
+    assert_ne!(visible_skeleton("paypal"), visible_skeleton("paypa"));
+  }
+}
@@ -0,0 +1,188 @@
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum QuotingStyle {
+  /// Output names as they are (-N)
+  #[default]
+  Literal = 0,
+
+  /// Quote names with '', if the shell needs it
+  Shell,
+
+  /// Always quote names with ''
+  ShellAlways,
+
+  /// Like Shell, but nonprintable characters are written as $'\n'
+  ShellEscape,
+
+  /// Like ShellAlways, but nonprintable characters are written as $'\n'
+  ShellEscapeAlways,
+
+  /// Enclose names in "" with C escapes (-Q)
+  C,
+
+  /// C escapes without quotes, spaces are escaped too (-b)
+  Escape,
+
+  /// Like C, with the quotes of the locale
+  Locale,
+
+  /// Like Locale, but "" outside of UTF-8 locales
+  CLocale
+}
+
+/// Characters the shell treats specially, including the control characters with C escapes
+/// (\a to \r). '#' and '~' only matter at the start of a word
+fn is_shell_special(c: char, first: bool) -> bool {
+  matches!(c, ' ' | '!' | '"' | '$' | '&' | '\'' | '(' | ')' | '*' | ';' | '<' | '=' | '>' | '?' | '[' | '\\' | '^' | '`' | '|')
+    || (first && matches!(c, '#' | '~'))
+    || ('\x07'..='\x0d').contains(&c)
+}
+
+/// Writes a nonprintable character as C escape, octal bytes if there is no short form
+fn push_c_escape(out: &mut String, c: char) {
+  match c {
+    '\x07' => out.push_str("\\a"),
+    '\x08' => out.push_str("\\b"),
+    '\x0c' => out.push_str("\\f"),
+    '\n' => out.push_str("\\n"),
+    '\r' => out.push_str("\\r"),
+    '\t' => out.push_str("\\t"),
+    '\x0b' => out.push_str("\\v"),
+    _ => {
+      let mut buf: [u8; 4] = [0; 4];
+      for byte in c.encode_utf8(&mut buf).bytes() {
+        out.push_str(&format!("\\{:03o}", byte));
+      }
+    }
+  }
+}
+
+/// Backslash escapes of the C, escape and locale styles
+fn backslash_escape(name: &str, left_quote: &str, right_quote: &str, escape_space: bool) -> String {
+  let mut out: String = String::from(left_quote);
+
+  for c in name.chars() {
+    match c {
+      '\\' => out.push_str("\\\\"),
+      ' ' if escape_space => out.push_str("\\ "),
+      c if c.is_control() => push_c_escape(&mut out, c),
+      c if right_quote.starts_with(c) => {
+        out.push('\\');
+        out.push(c);
+      },
+      c => out.push(c)
+    }
+  }
+
+  out.push_str(right_quote);
+  out
+}
+
+/// Quotes for the shell, using $'' for nonprintable characters if `escape` is set
+fn shell_quote(name: &str, always: bool, escape: bool) -> String {
+  let special = |(idx, c): (usize, char)| is_shell_special(c, idx == 0) || (escape && c.is_control());
+
+  if !always && !name.chars().enumerate().any(special) {
+    return name.to_string();
+  }
+
+  // Double quotes are more readable than '\'', if nothing else would need escaping in them
+  if name.contains('\'') && name.chars().enumerate().all(|(idx, c)| c == '\'' || c == ' ' || !special((idx, c))) {
+    return format!("\"{}\"", name);
+  }
+
+  let mut out: String = String::from("'");
+  let mut in_escape: bool = false;
+
+  for c in name.chars() {
+    if escape && c.is_control() {
+      if !in_escape {
+        out.push_str("'$'");
+        in_escape = true;
+      }
+      push_c_escape(&mut out, c);
+      continue;
+    }
+    if in_escape {
+      out.push_str("''");
+      in_escape = false;
+    }
+
+    if c == '\'' {
+      out.push_str("'\\''");
+    } else {
+      out.push(c);
+    }
+  }
+
+  out.push('\'');
+  out
+}
+
+/// Whether LC_ALL, LC_CTYPE or LANG select a UTF-8 locale
+fn locale_is_utf8() -> bool {
+  ["LC_ALL", "LC_CTYPE", "LANG"].iter()
+    .filter_map(|var| std::env::var(var).ok())
+    .find(|value| !value.is_empty())
+    .is_some_and(|value| {
+      let value: String = value.to_ascii_lowercase();
+      value.contains("utf-8") || value.contains("utf8")
+    })
+}
+
+impl QuotingStyle {
+  /// Writes the name as it is output in this style
+  pub fn quote(&self, name: &str) -> String {
+    match self {
+      QuotingStyle::Literal => name.to_string(),
+      QuotingStyle::Shell => shell_quote(name, false, false),
+      QuotingStyle::ShellAlways => shell_quote(name, true, false),
+      QuotingStyle::ShellEscape => shell_quote(name, false, true),
+      QuotingStyle::ShellEscapeAlways => shell_quote(name, true, true),
+      QuotingStyle::C => backslash_escape(name, "\"", "\"", false),
+      QuotingStyle::Escape => backslash_escape(name, "", "", true),
+      QuotingStyle::Locale | QuotingStyle::CLocale if locale_is_utf8() => {
+        backslash_escape(name, "\u{2018}", "\u{2019}", false)
+      },
+      QuotingStyle::Locale => backslash_escape(name, "'", "'", false),
+      QuotingStyle::CLocale => backslash_escape(name, "\"", "\"", false)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::QuotingStyle;
+
+  /// Names with the outputs of GNU ls in the styles Shell, ShellAlways, ShellEscape, C and Escape
+  const CASES: [(&str, [&str; 5]); 10] = [
+    ("plain", ["plain", "'plain'", "plain", "\"plain\"", "plain"]),
+    ("a b", ["'a b'", "'a b'", "'a b'", "\"a b\"", "a\\ b"]),
+    ("it's", ["\"it's\"", "\"it's\"", "\"it's\"", "\"it's\"", "it's"]),
+    ("a'b c$", ["'a'\\''b c$'", "'a'\\''b c$'", "'a'\\''b c$'", "\"a'b c$\"", "a'b\\ c$"]),
+    ("nl\nx", ["'nl\nx'", "'nl\nx'", "'nl'$'\\n''x'", "\"nl\\nx\"", "nl\\nx"]),
+    ("\x01", ["\x01", "'\x01'", "''$'\\001'", "\"\\001\"", "\\001"]),
+    ("#x", ["'#x'", "'#x'", "'#x'", "\"#x\"", "#x"]),
+    ("a#", ["a#", "'a#'", "a#", "\"a#\"", "a#"]),
+    ("q\"", ["'q\"'", "'q\"'", "'q\"'", "\"q\\\"\"", "q\""]),
+    ("back\\s", ["'back\\s'", "'back\\s'", "'back\\s'", "\"back\\\\s\"", "back\\\\s"])
+  ];
+
+  #[test]
+  fn quote_like_gnu_ls() {
+    let styles: [QuotingStyle; 5] = [
+      QuotingStyle::Shell, QuotingStyle::ShellAlways, QuotingStyle::ShellEscape, QuotingStyle::C, QuotingStyle::Escape
+    ];
+    for (name, expected) in CASES {
+      for (style, expected) in styles.iter().zip(expected) {
+        assert_eq!(style.quote(name), expected, "{:?} in {:?}", name, style);
+      }
+    }
+  }
+
+  #[test]
+  fn escape_always_quotes_printable_parts() {
+    assert_eq!(QuotingStyle::ShellEscapeAlways.quote("plain"), "'plain'");
+    assert_eq!(QuotingStyle::ShellEscapeAlways.quote("nl\nx"), "'nl'$'\\n''x'");
+    assert_eq!(QuotingStyle::Literal.quote("nl\nx"), "nl\nx");
+  }
+}
@@ -0,0 +1,238 @@
+// Standard Libraries
+use std::cmp::Ordering;
+use std::rc::{Rc, Weak};
+
+// Project Modules
+use super::{FileInfo, TimeType};
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum SortType {
+  /// Sort alphabetically by file name
+  #[default]
+  Name,
+  /// Newest file first
+  Time(TimeType),
+  /// Largest file first
+  Size,
+  /// Alphabetically by extension
+  Extension,
+  /// Natural sort of (version) numbers within the file name
+  Version,
+  /// Narrowest file name first
+  Width,
+  /// Directory order
+  None
+}
+
+/// Compares file names the way lrs always did: case insensitive first.
+/// Exact ties are broken bytewise, so the order never depends on the directory order.
+fn cmp_name(a: &str, b: &str) -> Ordering {
+  a.to_lowercase().cmp(&b.to_lowercase())
+    .then_with(|| a.cmp(b))
+}
+
+/// Everything from the last '.' on, empty if there is none
+fn extension(name: &str) -> &str {
+  match name.rfind('.') {
+    Some(idx) => &name[idx..],
+    None => ""
+  }
+}
+
+fn cmp_size(a: &FileInfo, b: &FileInfo) -> Ordering {
+  let a_size: u64 = a.metadata.as_ref().map_or(0, |m| m.len());
+  let b_size: u64 = b.metadata.as_ref().map_or(0, |m| m.len());
+
+  b_size.cmp(&a_size)
+    .then_with(|| cmp_name(&a.name, &b.name))
+}
+
+fn cmp_time(a: &FileInfo, b: &FileInfo, time_type: TimeType) -> Ordering {
+  let a_time: Option<(i64, i64)> = a.metadata.as_ref().and_then(|m| time_type.timestamp(m));
+  let b_time: Option<(i64, i64)> = b.metadata.as_ref().and_then(|m| time_type.timestamp(m));
+
+  b_time.cmp(&a_time)
+    .then_with(|| cmp_name(&a.name, &b.name))
+}
+
+fn cmp_extension(a: &FileInfo, b: &FileInfo) -> Ordering {
+  cmp_name(extension(&a.name), extension(&b.name))
+    .then_with(|| cmp_name(&a.name, &b.name))
+}
+
+fn cmp_version(a: &FileInfo, b: &FileInfo) -> Ordering {
+  filevercmp(a.name.as_bytes(), b.name.as_bytes())
+    .then_with(|| a.name.cmp(&b.name))
+}
+
+fn cmp_width(a: &FileInfo, b: &FileInfo) -> Ordering {
+  a.width.cmp(&b.width)
+    .then_with(|| cmp_name(&a.name, &b.name))
+}
+
+/// Length of the name without its suffix, as defined by the regular expression
+/// `(\.[A-Za-z~][A-Za-z0-9~]*)*$` (see gnulib's filevercmp)
+fn file_prefix_len(s: &[u8]) -> usize {
+  let n: usize = s.len();
+  let mut prefix_len: usize = 0;
+  let mut i: usize = 0;
+
+  while i < n {
+    i += 1;
+    prefix_len = i;
+    while i + 1 < n && s[i] == b'.' && (s[i + 1].is_ascii_alphabetic() || s[i + 1] == b'~') {
+      i += 2;
+      while i < n && (s[i].is_ascii_alphanumeric() || s[i] == b'~') {
+        i += 1;
+      }
+    }
+  }
+
+  prefix_len
+}
+
+/// Weight of a non-digit character: '~' before the end of the string, before letters, before
+/// everything else
+fn order(s: &[u8], pos: usize) -> i32 {
+  match s.get(pos) {
+    None => -1,
+    Some(c) if c.is_ascii_digit() => 0,
+    Some(c) if c.is_ascii_alphabetic() => *c as i32,
+    Some(b'~') => -2,
+    Some(c) => *c as i32 + u8::MAX as i32 + 1
+  }
+}
+
+fn verrevcmp(s1: &[u8], s2: &[u8]) -> Ordering {
+  let (mut s1_pos, mut s2_pos): (usize, usize) = (0, 0);
+
+  while s1_pos < s1.len() || s2_pos < s2.len() {
+    let mut first_diff: Ordering = Ordering::Equal;
+
+    while (s1_pos < s1.len() && !s1[s1_pos].is_ascii_digit())
+      || (s2_pos < s2.len() && !s2[s2_pos].is_ascii_digit()) {
+      let s1_c: i32 = order(s1, s1_pos);
+      let s2_c: i32 = order(s2, s2_pos);
+      if s1_c != s2_c {
+        return s1_c.cmp(&s2_c);
+      }
+      s1_pos += 1;
+      s2_pos += 1;
+    }
+
+    while s1_pos < s1.len() && s1[s1_pos] == b'0' { s1_pos += 1; }
+    while s2_pos < s2.len() && s2[s2_pos] == b'0' { s2_pos += 1; }
+
+    while s1_pos < s1.len() && s2_pos < s2.len()
+      && s1[s1_pos].is_ascii_digit() && s2[s2_pos].is_ascii_digit() {
+      if first_diff == Ordering::Equal {
+        first_diff = s1[s1_pos].cmp(&s2[s2_pos]);
+      }
+      s1_pos += 1;
+      s2_pos += 1;
+    }
+
+    // The longer number is the bigger one
+    if s1_pos < s1.len() && s1[s1_pos].is_ascii_digit() { return Ordering::Greater; }
+    if s2_pos < s2.len() && s2[s2_pos].is_ascii_digit() { return Ordering::Less; }
+    if first_diff != Ordering::Equal { return first_diff; }
+  }
+
+  Ordering::Equal
+}
+
+/// Version comparison of file names, compatible to GNU's `ls -v` and `sort -V`
+pub fn filevercmp(a: &[u8], b: &[u8]) -> Ordering {
+  if a.is_empty() || b.is_empty() {
+    return (!a.is_empty()).cmp(&!b.is_empty());
+  }
+
+  // "." first, then "..", then other hidden files, then everything else
+  if a[0] == b'.' || b[0] == b'.' {
+    if a[0] != b[0] {
+      return if a[0] == b'.' { Ordering::Less } else { Ordering::Greater };
+    }
+    for special in [&b"."[..], &b".."[..]] {
+      match (a == special, b == special) {
+        (true, true) => return Ordering::Equal,
+        (true, false) => return Ordering::Less,
+        (false, true) => return Ordering::Greater,
+        (false, false) => ()
+      }
+    }
+  }
+
+  let a_prefix_len: usize = file_prefix_len(a);
+  let b_prefix_len: usize = file_prefix_len(b);
+  let one_pass_only: bool = a_prefix_len == a.len() && b_prefix_len == b.len();
+
+  match verrevcmp(&a[..a_prefix_len], &b[..b_prefix_len]) {
+    Ordering::Equal if !one_pass_only => verrevcmp(a, b),
+    result => result
+  }
+}
+
+trait SortFileInfoCollection {
+  fn sort(&mut self, sort_type: SortType, reverse: bool);
+}
+
+impl SortFileInfoCollection for Vec<Weak<FileInfo>> {
+  fn sort(&mut self, sort_type: SortType, reverse: bool) {
+    // Directory order can't be reversed
+    if sort_type == SortType::None {
+      return;
+    }
+
+    let cmp = |a: &FileInfo, b: &FileInfo| match sort_type {
+      SortType::Name => cmp_name(&a.name, &b.name),
+      SortType::Time(time_type) => cmp_time(a, b, time_type),
+      SortType::Size => cmp_size(a, b),
+      SortType::Extension => cmp_extension(a, b),
+      SortType::Version => cmp_version(a, b),
+      SortType::Width => cmp_width(a, b),
+      SortType::None => Ordering::Equal
+    };
+
+    self.sort_by(|a, b| {
+      let (a, b): (Rc<FileInfo>, Rc<FileInfo>) = (a.upgrade().unwrap(), b.upgrade().unwrap());
+      if reverse { cmp(&b, &a) } else { cmp(&a, &b) }
+    });
+  }
+}
+
+pub trait SortedFileInfoCollection {
+    fn sorted_files(&self, sort_type: SortType, reverse: bool) -> Vec<Weak<FileInfo>>;
+}
+
+impl SortedFileInfoCollection for Vec<Rc<FileInfo>> {
+  fn sorted_files(&self, sort_type: SortType, reverse: bool) -> Vec<Weak<FileInfo>> {
+    let mut out: Vec<Weak<FileInfo>> = Vec::with_capacity(self.len());
+
+    self.iter().for_each(|f| out.push(Rc::downgrade(f)));
+    out.sort(sort_type, reverse);
+
+    out
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::filevercmp;
+
+  #[test]
+  fn filevercmp_orders_like_sort_v() {
+    let expected: [&str; 18] = [
+      ".", "..", ".hidden", "A", "a~", "a", "a1", "a1.tar", "a1.tar.gz", "a2", "a10", "a.1",
+      "a_", "b", "file-1.9.tar.gz", "file-1.10.tar.gz", "rc1~", "rc1"
+    ];
+    let mut names: Vec<&str> = expected.iter().rev().copied().collect();
+    names.sort_by(|a, b| filevercmp(a.as_bytes(), b.as_bytes()));
+    assert_eq!(names, expected);
+  }
+
+  #[test]
+  fn filevercmp_puts_empty_names_first() {
+    assert!(filevercmp(b"", b"a").is_lt());
+    assert!(filevercmp(b"", b"").is_eq());
+  }
+}
@@ -0,0 +1,32 @@
+// Standard Libraries
+use std::fs::{create_dir_all, remove_dir_all};
+use std::path::{Path, PathBuf};
+
+/// Empty directory for the files of one test, removed with its content when dropped
+pub struct TestDir {
+  path: PathBuf
+}
+
+impl TestDir {
+  /// Creates the directory, named after the test and the process
+  pub fn new(name: &str) -> Self {
+    let path: PathBuf = std::env::temp_dir().join(format!("lrs-{}-{}", name, std::process::id()));
+    let _ = remove_dir_all(&path);
+    create_dir_all(&path).unwrap();
+    TestDir { path }
+  }
+
+  pub fn path(&self) -> &Path {
+    &self.path
+  }
+
+  pub fn join(&self, name: &str) -> PathBuf {
+    self.path.join(name)
+  }
+}
+
+impl Drop for TestDir {
+  fn drop(&mut self) {
+    let _ = remove_dir_all(&self.path);
+  }
+}
@@ -0,0 +1,44 @@
+// Standard Libraries
+use std::fs::Metadata;
+use std::os::linux::fs::MetadataExt;
+use std::time::UNIX_EPOCH;
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum TimeType {
+  /// Time of last modification
+  #[default]
+  Modification = 0,
+
+  /// Time of last status change
+  Change,
+
+  /// Time of last access
+  Access,
+
+  /// Time of creation, not supported by every filesystem
+  Birth
+}
+
+impl TimeType {
+  /// Seconds and nanoseconds since the epoch. None, if the filesystem does not provide it
+  pub fn timestamp(&self, metadata: &Metadata) -> Option<(i64, i64)> {
+    match self {
+      TimeType::Modification => Some((metadata.st_mtime(), metadata.st_mtime_nsec())),
+      TimeType::Change => Some((metadata.st_ctime(), metadata.st_ctime_nsec())),
+      TimeType::Access => Some((metadata.st_atime(), metadata.st_atime_nsec())),
+      TimeType::Birth => {
+        let created = metadata.created().ok()?;
+
+        match created.duration_since(UNIX_EPOCH) {
+          Ok(d) => Some((d.as_secs() as i64, d.subsec_nanos() as i64)),
+          // Before 1970: round towards negative infinity, like struct timespec
+          Err(e) => {
+            let d = e.duration();
+            let (secs, nsecs) = (-(d.as_secs() as i64), d.subsec_nanos() as i64);
+            if nsecs == 0 { Some((secs, 0)) } else { Some((secs - 1, 1_000_000_000 - nsecs)) }
+          }
+        }
+      }
+    }
+  }
+}
@@ -20,7 +20,6 @@ use working_set::*;
 use print::print_current_files;
 
 use std::sync::{Arc, atomic::{AtomicBool, Ordering}};
-use ctrlc;
 
 fn main() -> ExitCode {
   let abort_flag = Arc::new(AtomicBool::new(false));
@@ -31,7 +30,12 @@ fn main() -> ExitCode {
       flag_clone.store(true, Ordering::SeqCst);
   }).expect("Failed setting up SIGINT handler");
   
-  let mut working_set: WorkingSet = WorkingSet::new(Args::parse(), Some(abort_flag));
+  let args: Args = Args::parse();
+  if args.dircolors || args.print_database {
+    return print::dircolors::dircolors_mode(&args).into();
+  }
+
+  let mut working_set: WorkingSet = WorkingSet::new(args, Some(abort_flag));
 
   if working_set.args.recursive { 
     working_set.loop_manager.init_loop_detection(); 
@@ -39,8 +43,11 @@ fn main() -> ExitCode {
   
   // Output arguments 
   working_set.process_argument_paths();
+  working_set.warn_lookalike_names();
   working_set.sort_files();
-  working_set.extract_dirs_from_files(None, true);
+  if !working_set.args.directory {
+    working_set.extract_dirs_from_files(None, true);
+  }
   
   let mut print_dir_name: bool = true;
   let n_files: usize = working_set.sorted_files.len(); 
@@ -59,20 +66,17 @@ fn main() -> ExitCode {
 
   while working_set.pending_dirs.has_next(){
     // Signals
-    if let Some(abort_flag) = &working_set.abort_flag {
-      if abort_flag.load(Ordering::SeqCst) {
-          return working_set.exit_status.into();
-      }
+    if let Some(abort_flag) = &working_set.abort_flag
+      && abort_flag.load(Ordering::SeqCst) {
+        return working_set.exit_status.into();
     }
 
     // dequeue_directory() can't be none, due to has_next() == true within this scope
     let this_pend = working_set.pending_dirs.dequeue_directory().unwrap();
     
-    if working_set.args.recursive {
-      if this_pend.name == None {
-        working_set.loop_manager.dev_ino_pop();
-        continue;
-      }
+    if working_set.args.recursive && this_pend.name.is_none() {
+      working_set.loop_manager.dev_ino_pop();
+      continue;
     }
     
     // this_pend.name is some at this point!
@@ -0,0 +1,366 @@
+// Standard Libraries
+use std::io::Read;
+
+// Project Modules
+use crate::args::Args;
+use crate::exit_status::ExitStatus;
+use crate::filesystem::Glob;
+use super::Error;
+
+/// Built-in database, like the one of GNU dircolors. Unlike the defaults of lrs without
+/// LS_COLORS, it colors extensions and sets MULTIHARDLINK and CAPABILITY to 00
+pub const DEFAULT_DATABASE: &str = "\
+# Configuration file for lrs --dircolors, a utility to help you set the
+# LS_COLORS environment variable used by lrs and GNU ls with the --color option.
+# The keywords COLOR, OPTIONS, and EIGHTBIT are recognized but ignored.
+# Global config options can be specified before TERM or COLORTERM entries.
+# Below are TERM or COLORTERM entries, which can be glob patterns, which
+# restrict following config to systems with matching environment variables.
+COLORTERM ?*
+TERM Eterm
+TERM ansi
+TERM *color*
+TERM con[0-9]*x[0-9]*
+TERM cons25
+TERM console
+TERM cygwin
+TERM *direct*
+TERM dtterm
+TERM gnome
+TERM hurd
+TERM jfbterm
+TERM konsole
+TERM kterm
+TERM linux
+TERM linux-c
+TERM mlterm
+TERM putty
+TERM rxvt*
+TERM screen*
+TERM st
+TERM terminator
+TERM tmux*
+TERM vt100
+TERM xterm*
+# Attribute codes:
+# 00=none 01=bold 04=underscore 05=blink 07=reverse 08=concealed
+# Text color codes:
+# 30=black 31=red 32=green 33=yellow 34=blue 35=magenta 36=cyan 37=white
+# Background color codes:
+# 40=black 41=red 42=green 43=yellow 44=blue 45=magenta 46=cyan 47=white
+#NORMAL 00 # no color code at all
+#FILE 00 # regular file: use no color at all
+RESET 0 # reset to \"normal\" color
+DIR 01;34 # directory
+LINK 01;36 # symbolic link. (If you set this to 'target' instead of a
+ # numerical value, the color is as for the file pointed to.)
+MULTIHARDLINK 00 # regular file with more than one link
+FIFO 33 # pipe
+SOCK 01;35 # socket
+DOOR 01;35 # door
+BLK 01;33 # block device driver
+CHR 01;33 # character device driver
+#ORPHAN 00 # symlink to nonexistent file, or non-stat'able file ...
+#MISSING 00 # ... and the files they point to
+SETUID 37;41 # file that is setuid (u+s)
+SETGID 30;43 # file that is setgid (g+s)
+CAPABILITY 00 # file with capability
+STICKY_OTHER_WRITABLE 30;42 # dir that is sticky and other-writable (+t,o+w)
+OTHER_WRITABLE 34;42 # dir that is other-writable (o+w) and not sticky
+STICKY 37;44 # dir with the sticky bit set (+t) and not other-writable
+# This is for files with execute permission:
+EXEC 01;32
+# List any file extensions like '.gz' or '.tar' that you would like ls
+# to color below. Put the extension, a space, and the color init string.
+# (and any comments you want to add after a '#')
+# archives or compressed (bright red)
+.tar 01;31
+.tgz 01;31
+.zip 01;31
+.gz 01;31
+.xz 01;31
+.zst 01;31
+.bz2 01;31
+.deb 01;31
+.rpm 01;31
+.jar 01;31
+.rar 01;31
+.7z 01;31
+# image formats
+.jpg 01;35
+.jpeg 01;35
+.gif 01;35
+.bmp 01;35
+.png 01;35
+.svg 01;35
+.webp 01;35
+.mkv 01;35
+.mp4 01;35
+.webm 01;35
+.avi 01;35
+# audio formats
+.flac 00;36
+.mp3 00;36
+.ogg 00;36
+.opus 00;36
+.wav 00;36
+# backup files
+*~ 00;90
+*# 00;90
+.bak 00;90
+.old 00;90
+.orig 00;90
+.swp 00;90
+.tmp 00;90
+";
+
+/// Keywords of the database format and their LS_COLORS keys
+const KEYWORDS: [(&str, &str); 37] = [
+  ("NORMAL", "no"), ("NORM", "no"), ("FILE", "fi"), ("RESET", "rs"), ("DIR", "di"),
+  ("LNK", "ln"), ("LINK", "ln"), ("SYMLINK", "ln"), ("ORPHAN", "or"), ("MISSING", "mi"),
+  ("FIFO", "pi"), ("PIPE", "pi"), ("SOCK", "so"), ("BLK", "bd"), ("BLOCK", "bd"),
+  ("CHR", "cd"), ("CHAR", "cd"), ("DOOR", "do"), ("EXEC", "ex"), ("LEFT", "lc"),
+  ("LEFTCODE", "lc"), ("RIGHT", "rc"), ("RIGHTCODE", "rc"), ("END", "ec"), ("ENDCODE", "ec"),
+  ("SUID", "su"), ("SETUID", "su"), ("SGID", "sg"), ("SETGID", "sg"), ("STICKY", "st"),
+  ("OTHER_WRITABLE", "ow"), ("OWR", "ow"), ("STICKY_OTHER_WRITABLE", "tw"), ("OWT", "tw"),
+  ("CAPABILITY", "ca"), ("MULTIHARDLINK", "mh"), ("CLRTOEOL", "cl")
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ShellSyntax {
+  Bourne,
+  C
+}
+
+impl ShellSyntax {
+  /// Guesses the syntax from $SHELL: C shell syntax for csh and tcsh, else Bourne shell syntax
+  pub fn from_env() -> Self {
+    match std::env::var("SHELL") {
+      Ok(shell) if shell.rsplit('/').next().unwrap_or("").ends_with("csh") => ShellSyntax::C,
+      _ => ShellSyntax::Bourne
+    }
+  }
+}
+
+/// Splits a line into keyword and argument, ignoring comments
+fn parse_line(line: &str) -> Option<(&str, Option<&str>)> {
+  let line: &str = line.trim_start();
+  if line.is_empty() || line.starts_with('#') {
+    return None;
+  }
+
+  let (keyword, rest) = match line.find(char::is_whitespace) {
+    Some(idx) => (&line[..idx], line[idx..].trim_start()),
+    None => (line, "")
+  };
+  let arg: &str = match rest.find('#') {
+    Some(idx) => rest[..idx].trim_end(),
+    None => rest.trim_end()
+  };
+
+  Some((keyword, if arg.is_empty() { None } else { Some(arg) }))
+}
+
+/// Appends to LS_COLORS, escaping single quotes for the shell, and ':' and '=' for LS_COLORS
+fn append_quoted(out: &mut String, s: &str) {
+  let mut need_backslash: bool = true;
+
+  for c in s.chars() {
+    match c {
+      '\'' => {
+        out.push_str("'\\'");
+        need_backslash = true;
+      }
+      '\\' | '^' => need_backslash = !need_backslash,
+      ':' | '=' => {
+        if need_backslash {
+          out.push('\\');
+        }
+        need_backslash = true;
+      }
+      _ => need_backslash = true
+    }
+    out.push(c);
+  }
+}
+
+#[derive(PartialEq)]
+enum TermState {
+  Global,
+  /// A TERM entry matched, later TERM entries of the same block are irrelevant
+  Sure,
+  Yes,
+  No
+}
+
+/// Compiles a database into the value of LS_COLORS. Like GNU dircolors, the errors of all
+/// lines are returned in the order they are reported, and unknown keywords outside of
+/// a matching TERM block are ignored
+pub fn compile_database(database: &str, file_name: &str) -> Result<String, Vec<Error>> {
+  let term: String = std::env::var("TERM").ok().filter(|t| !t.is_empty()).unwrap_or("none".into());
+  let colorterm: String = std::env::var("COLORTERM").unwrap_or_default();
+  let mut state: TermState = TermState::Global;
+  let mut out: String = String::new();
+  let mut errors: Vec<Error> = Vec::new();
+
+  for (line_no, line) in database.lines().enumerate() {
+    let (keyword, arg) = match parse_line(line) {
+      Some(parsed) => parsed,
+      None => continue
+    };
+    let arg: &str = match arg {
+      Some(arg) => arg,
+      None => {
+        errors.push(Error::MissingArgumentError {
+          file: file_name.into(),
+          line: line_no + 1,
+          keyword: keyword.into()
+        });
+        continue;
+      }
+    };
+
+    if keyword.eq_ignore_ascii_case("TERM") {
+      if state != TermState::Sure {
+        state = if Glob::new_plain(arg).matches(&term) { TermState::Sure } else { TermState::No };
+      }
+      continue;
+    }
+    if keyword.eq_ignore_ascii_case("COLORTERM") {
+      if state != TermState::Sure {
+        state = if Glob::new_plain(arg).matches(&colorterm) { TermState::Sure } else { TermState::No };
+      }
+      continue;
+    }
+
+    // Another TERM entry can cancel
+    if state == TermState::Sure {
+      state = TermState::Yes;
+    }
+    if state == TermState::No {
+      continue;
+    }
+
+    if keyword.starts_with('.') || keyword.starts_with('*') {
+      if keyword.starts_with('.') {
+        out.push('*');
+      }
+      append_quoted(&mut out, keyword);
+    } else if ["OPTIONS", "COLOR", "EIGHTBIT"].iter().any(|k| keyword.eq_ignore_ascii_case(k)) {
+      continue;
+    } else {
+      match KEYWORDS.iter().find(|(k, _)| keyword.eq_ignore_ascii_case(k)) {
+        Some((_, code)) => out.push_str(code),
+        None => {
+          if state == TermState::Yes {
+            errors.push(Error::UnrecognizedKeywordError {
+              file: file_name.into(),
+              line: line_no + 1,
+              keyword: keyword.into()
+            });
+          }
+          continue;
+        }
+      }
+    }
+    out.push('=');
+    append_quoted(&mut out, arg);
+    out.push(':');
+  }
+
+  if errors.is_empty() { Ok(out) } else { Err(errors) }
+}
+
+/// Reads the database from a file, "-" being stdin, or uses the built-in one
+pub fn read_database(file_name: Option<&str>) -> Result<String, Error> {
+  let to_error = |e: std::io::Error| Error::CannotReadDatabaseError {
+    file: file_name.unwrap_or_default().into(),
+    message: e.to_string()
+  };
+
+  match file_name {
+    None => Ok(DEFAULT_DATABASE.into()),
+    Some("-") => {
+      let mut database: String = String::new();
+      std::io::stdin().read_to_string(&mut database).map_err(to_error)?;
+      Ok(database)
+    }
+    Some(file_name) => std::fs::read_to_string(file_name).map_err(to_error)
+  }
+}
+
+/// Writes the shell code setting LS_COLORS
+pub fn ls_colors_assignment(ls_colors: &str, syntax: ShellSyntax) -> String {
+  match syntax {
+    ShellSyntax::Bourne => format!("LS_COLORS='{}';\nexport LS_COLORS\n", ls_colors),
+    ShellSyntax::C => format!("setenv LS_COLORS '{}'\n", ls_colors)
+  }
+}
+
+/// Entry point of --dircolors and --print-database
+pub fn dircolors_mode(args: &Args) -> ExitStatus {
+  let mut exit_status: ExitStatus = ExitStatus::default();
+
+  if args.print_database {
+    match args.paths.first() {
+      Some(operand) => exit_status.update(Error::DatabaseOperandError { operand: operand.clone() }.into(), true),
+      None => print!("{}", DEFAULT_DATABASE)
+    }
+    return exit_status;
+  }
+
+  if args.paths.len() > 1 {
+    exit_status.update(Error::ExtraOperandError { operand: args.paths[1].clone() }.into(), true);
+    return exit_status;
+  }
+
+  let file_name: Option<&str> = args.paths.first().map(|s| s.as_str());
+  let database: String = match read_database(file_name) {
+    Ok(database) => database,
+    Err(e) => {
+      exit_status.update(e.into(), true);
+      return exit_status;
+    }
+  };
+
+  match compile_database(&database, file_name.unwrap_or("<internal>")) {
+    Ok(ls_colors) => {
+      let syntax: ShellSyntax = {
+        if args.c_shell { ShellSyntax::C }
+        else if args.bourne_shell { ShellSyntax::Bourne }
+        else { ShellSyntax::from_env() }
+      };
+      print!("{}", ls_colors_assignment(&ls_colors, syntax));
+    }
+    Err(errors) => {
+      for error in errors {
+        exit_status.update(error.into(), true);
+      }
+    }
+  }
+
+  exit_status
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{compile_database, Error, DEFAULT_DATABASE};
+
+  #[test]
+  fn the_default_database_compiles() {
+    assert!(compile_database(DEFAULT_DATABASE, "<internal>").is_ok());
+  }
+
+  #[test]
+  fn errors_are_reported_and_parsing_continues() {
+    let database: &str = "FOO 1\nTERM *\nDIR 01;34\nBAR 2\nLINK\nFIFO 33\n";
+    let errors: Vec<Error> = compile_database(database, "db").unwrap_err();
+    assert!(matches!(errors.as_slice(), [
+      Error::UnrecognizedKeywordError { file, line: 4, keyword },
+      Error::MissingArgumentError { line: 5, .. }
+    ] if file == "db" && keyword == "BAR"));
+    assert_eq!(errors[0].to_string(), "db:4: unrecognized keyword BAR");
+
+    assert_eq!(compile_database("FOO 1\nDIR 01;34\n", "db").unwrap(), "di=01;34:");
+  }
+}
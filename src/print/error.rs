@@ -1,32 +1,57 @@
-// Standard Libraries
-//use std::path::PathBuf;
-
-//use super::ExitStatus;
-
-//pub type Result<T> = std::result::Result<T, Error>;
+// Project Modules
+use super::{ExitStatus, IntoExitStatus};
 
+// Every variant names the kind of error
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum Error {
-  
+  CannotReadDatabaseError {
+    file: String,
+    message: String
+  },
+  MissingArgumentError {
+    file: String,
+    line: usize,
+    keyword: String
+  },
+  UnrecognizedKeywordError {
+    file: String,
+    line: usize,
+    keyword: String
+  },
+  ExtraOperandError {
+    operand: String
+  },
+  DatabaseOperandError {
+    operand: String
+  },
+  UnrecognizedPrefixError {
+    prefix: String
+  },
+  UnparsableColorsError
 }
 
-/*impl From<Error> for ExitStatus{
-  fn from(err: Error) -> Self {
-    //match err {
-    //  Error:: { serious,.. } |
-    //  Error:: { serious,.. } | => {
-    //    serious.into()
-    //  },
-    //}
+impl IntoExitStatus for Error {
+  fn into_exit_status(self, cli_arg: bool) -> ExitStatus {
+    ExitStatus::from(cli_arg)
   }
-}*/
+}
 
 impl std::error::Error for Error {}
 
 impl std::fmt::Display for Error {
   fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::result::Result<(), std::fmt::Error> {
     match self {
-      _ => write!(fmt, "Print error"),
+      Self::CannotReadDatabaseError { file, message } => write!(fmt, "{}: {}", file, message),
+      Self::MissingArgumentError { file, line, keyword } => 
+        write!(fmt, "{}:{}: invalid line; missing second token after '{}'", file, line, keyword),
+      Self::UnrecognizedKeywordError { file, line, keyword } => 
+        write!(fmt, "{}:{}: unrecognized keyword {}", file, line, keyword),
+      Self::ExtraOperandError { operand } => write!(fmt, "extra operand '{}'", operand),
+      Self::DatabaseOperandError { operand } => write!(fmt,
+        "extra operand '{}'\nfile operands cannot be combined with --print-database", operand),
+      Self::UnrecognizedPrefixError { prefix } => write!(fmt, "unrecognized prefix: '{}'", prefix),
+      Self::UnparsableColorsError => write!(fmt, "unparsable value for LS_COLORS environment variable"),
     }
   }
 }
@@ -0,0 +1,18 @@
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+  /// One file per line with its metadata (-l)
+  Long = 0,
+
+  /// One file per line (-1)
+  OnePerLine,
+
+  /// Files in columns, sorted vertically (-C)
+  #[default]
+  ManyPerLine,
+
+  /// Files in columns, sorted horizontally (-x)
+  Horizontal,
+
+  /// Files separated by ", " (-m)
+  WithCommas
+}
@@ -3,7 +3,8 @@ pub struct FormatInfo {
   pub hard_link_length: usize, 
   pub user_length: usize,
   pub group_length: usize,  
-  //pub block_length: usize,
+  pub inode_length: usize,
+  pub block_length: usize,
   pub major_length: usize,
   pub minor_length: usize,
   pub file_size_length: usize
@@ -15,7 +16,8 @@ impl FormatInfo {
       hard_link_length: 0,
       user_length: 0, 
       group_length: 0, 
-      //block_length: 0,
+      inode_length: 0,
+      block_length: 0,
       major_length: 0,
       minor_length: 0,
       file_size_length: 0 
@@ -40,11 +42,17 @@ impl FormatInfo {
       }
     }
 
-  /*pub fn update_block_length(&mut self, block_length: usize) {
+  pub fn update_inode_length(&mut self, inode_length: usize) {
+    if self.inode_length < inode_length {
+      self.inode_length = inode_length;
+    }
+  }
+
+  pub fn update_block_length(&mut self, block_length: usize) {
     if self.block_length < block_length {
       self.block_length = block_length;
     }
-  }*/
+  }
   
   fn update_major_length(&mut self, major_length: usize) {
     if self.major_length < major_length {
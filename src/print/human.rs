@@ -0,0 +1,284 @@
+/// Largest exponent of the unit suffixes
+const EXPONENT_MAX: usize = 10;
+const POWER_LETTERS: [char; EXPONENT_MAX + 1] = ['\0', 'K', 'M', 'G', 'T', 'P', 'E', 'Z', 'Y', 'R', 'Q'];
+
+/// How to write sizes, a port of gnulib's human_readable() options
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct HumanOptions {
+  /// Pick the unit by the size of the number (-h, --si)
+  pub autoscale: bool,
+  /// Powers of 1024 instead of 1000
+  pub base_1024: bool,
+  /// Append the unit suffix ("K", "M", ...)
+  pub si: bool,
+  /// Append "B" (or "iB") to the suffix
+  pub b_suffix: bool,
+  /// Separate thousands with ','
+  pub group_digits: bool
+}
+
+/// Unit sizes are written in, together with the format
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct BlockSize {
+  pub options: HumanOptions,
+  pub size: u64
+}
+
+impl BlockSize {
+  /// Plain byte counts, the default for file sizes
+  pub fn bytes() -> Self {
+    BlockSize { options: HumanOptions::default(), size: 1 }
+  }
+
+  /// Powers of 1024 (-h) or of 1000 (--si)
+  pub fn human(base_1024: bool) -> Self {
+    BlockSize {
+      options: HumanOptions { autoscale: true, base_1024, si: true, ..HumanOptions::default() },
+      size: 1
+    }
+  }
+
+  /// Without POSIXLY_CORRECT blocks are 1024 bytes
+  fn default_block_size() -> Self {
+    let size: u64 = if std::env::var_os("POSIXLY_CORRECT").is_some() { 512 } else { 1024 };
+    BlockSize { options: HumanOptions::default(), size }
+  }
+
+  /// Block sizes from LS_BLOCK_SIZE, BLOCK_SIZE or BLOCKSIZE, invalid values are ignored.
+  /// Returns the block size and the unit of file sizes, which stay in bytes unless
+  /// LS_BLOCK_SIZE or BLOCK_SIZE is set
+  pub fn from_env() -> (Self, Self) {
+    let spec: Option<String> = ["LS_BLOCK_SIZE", "BLOCK_SIZE", "BLOCKSIZE"].iter()
+      .find_map(|var| std::env::var(var).ok());
+    let block_size: BlockSize = spec.as_deref()
+      .and_then(|spec| Self::parse(spec).ok())
+      .unwrap_or_else(Self::default_block_size);
+
+    if std::env::var_os("LS_BLOCK_SIZE").is_some() || std::env::var_os("BLOCK_SIZE").is_some() {
+      (block_size, block_size)
+    }
+    else {
+      (block_size, Self::bytes())
+    }
+  }
+
+  /// Parses SIZE of --block-size, like "1024", "K", "1MiB", "MB", "human-readable" or "'1".
+  /// A leading "'" groups thousands, a suffix without number is also printed after sizes
+  pub fn parse(spec: &str) -> Result<Self, String> {
+    let invalid = || format!("invalid block size '{}'", spec);
+    let too_large = || format!("block size '{}' is too large", spec);
+    let mut options: HumanOptions = HumanOptions::default();
+
+    let spec_without_quote: &str = match spec.strip_prefix('\'') {
+      Some(rest) => {
+        options.group_digits = true;
+        rest
+      },
+      None => spec
+    };
+
+    if spec_without_quote == "human-readable" || spec_without_quote == "si" {
+      let human: BlockSize = Self::human(spec_without_quote == "human-readable");
+      return Ok(BlockSize { options: HumanOptions { group_digits: options.group_digits, ..human.options }, size: 1 });
+    }
+
+    let digits_end: usize = spec_without_quote.find(|c: char| !c.is_ascii_digit()).unwrap_or(spec_without_quote.len());
+    let (number, suffix) = spec_without_quote.split_at(digits_end);
+
+    let mut size: u64 = {
+      if number.is_empty() { 1 }
+      else { number.parse::<u64>().map_err(|_| too_large())? }
+    };
+
+    if !suffix.is_empty() {
+      let mut chars = suffix.chars();
+      let exponent: u32 = match chars.next().map(|c| c.to_ascii_uppercase()) {
+        Some(c) => match POWER_LETTERS.iter().position(|l| *l == c) {
+          Some(exponent) if exponent > 0 => exponent as u32,
+          _ => return Err(invalid())
+        },
+        None => return Err(invalid())
+      };
+      let base: u64 = match chars.as_str() {
+        "" | "iB" => 1024,
+        "B" => 1000,
+        _ => return Err(invalid())
+      };
+      let factor: u64 = base.checked_pow(exponent).ok_or_else(too_large)?;
+      size = size.checked_mul(factor).ok_or_else(too_large)?;
+
+      // Only a unit: show it after every size
+      if number.is_empty() {
+        options.si = true;
+        options.b_suffix = suffix.ends_with('B');
+        options.base_1024 = !suffix.ends_with('B') || suffix.ends_with("iB");
+      }
+    }
+    else if number.is_empty() {
+      return Err(invalid());
+    }
+
+    if size == 0 {
+      return Err(invalid());
+    }
+
+    Ok(BlockSize { options, size })
+  }
+
+  /// Converts `n` units of `from_block_size` bytes into this block size, rounding up like GNU ls
+  pub fn human_readable(&self, n: u64, from_block_size: u64) -> String {
+    let options: HumanOptions = self.options;
+    let to_block_size: u128 = self.size as u128;
+    let base: u128 = if options.base_1024 { 1024 } else { 1000 };
+
+    // Integer part, the first decimal and how the rest compares to half a tenth:
+    // 0 = nothing left, 1 = less than half, 2 = exactly half, 3 = more than half
+    let bytes: u128 = n as u128 * from_block_size as u128;
+    let mut amount: u128 = bytes / to_block_size;
+    let r10: u128 = (bytes % to_block_size) * 10;
+    let r2: u128 = (r10 % to_block_size) * 2;
+    let mut tenths: u128 = r10 / to_block_size;
+    let mut rounding: u128 = if r2 < to_block_size { (0 < r2) as u128 } else { 2 + (to_block_size < r2) as u128 };
+
+    let mut exponent: Option<usize> = None;
+    let mut fraction: Option<u128> = None;
+
+    if options.autoscale {
+      let mut exp: usize = 0;
+
+      if base <= amount {
+        while base <= amount && exp < EXPONENT_MAX {
+          let r10: u128 = (amount % base) * 10 + tenths;
+          let r2: u128 = (r10 % base) * 2 + (rounding >> 1);
+          amount /= base;
+          tenths = r10 / base;
+          rounding = if r2 < base { (r2 + rounding != 0) as u128 } else { 2 + (base < r2 + rounding) as u128 };
+          exp += 1;
+        }
+
+        // Show one decimal for small numbers, like "1.5K"
+        if amount < 10 {
+          if 0 < rounding {
+            tenths += 1;
+            rounding = 0;
+            if tenths == 10 {
+              amount += 1;
+              tenths = 0;
+            }
+          }
+          if amount < 10 {
+            fraction = Some(tenths);
+            tenths = 0;
+            rounding = 0;
+          }
+        }
+      }
+
+      exponent = Some(exp);
+    }
+
+    // Round up
+    if 0 < tenths + rounding {
+      amount += 1;
+      if let Some(exp) = exponent && amount == base && exp < EXPONENT_MAX {
+        exponent = Some(exp + 1);
+        fraction = Some(0);
+        amount = 1;
+      }
+    }
+
+    let mut out: String = {
+      if options.group_digits { group_digits(&amount.to_string()) }
+      else { amount.to_string() }
+    };
+    if let Some(fraction) = fraction {
+      out.push('.');
+      out.push_str(&fraction.to_string());
+    }
+
+    if options.si {
+      // Without autoscale the unit is the block size
+      let exponent: usize = exponent.unwrap_or_else(|| {
+        let mut exponent: usize = 0;
+        let mut power: u128 = 1;
+        while power < to_block_size && exponent < EXPONENT_MAX {
+          power *= base;
+          exponent += 1;
+        }
+        exponent
+      });
+
+      if exponent > 0 {
+        out.push(if !options.base_1024 && exponent == 1 { 'k' } else { POWER_LETTERS[exponent] });
+      }
+      if options.b_suffix {
+        if options.base_1024 && exponent > 0 {
+          out.push('i');
+        }
+        out.push('B');
+      }
+    }
+
+    out
+  }
+}
+
+/// Inserts ',' between groups of three digits
+fn group_digits(digits: &str) -> String {
+  let mut out: String = String::with_capacity(digits.len() + digits.len() / 3);
+
+  for (idx, c) in digits.chars().enumerate() {
+    if idx > 0 && (digits.len() - idx).is_multiple_of(3) {
+      out.push(',');
+    }
+    out.push(c);
+  }
+
+  out
+}
+
+#[cfg(test)]
+mod tests {
+  use super::BlockSize;
+
+  const SIZES: [u64; 8] = [0, 1, 1000, 1023, 1024, 1536, 10240, 5000000000];
+
+  fn sizes(block_size: BlockSize) -> Vec<String> {
+    SIZES.iter().map(|n| block_size.human_readable(*n, 1)).collect()
+  }
+
+  #[test]
+  fn parse_block_sizes() {
+    assert_eq!(BlockSize::parse("1024").unwrap().size, 1024);
+    assert_eq!(BlockSize::parse("1KiB").unwrap().size, 1024);
+    assert_eq!(BlockSize::parse("2MB").unwrap().size, 2000000);
+    assert_eq!(BlockSize::parse("k").unwrap().size, 1024);
+    assert_eq!(BlockSize::parse("human-readable").unwrap(), BlockSize::human(true));
+    assert!(BlockSize::parse("'1").unwrap().options.group_digits);
+    assert!(BlockSize::parse("0").is_err());
+    assert!(BlockSize::parse("").is_err());
+    assert!(BlockSize::parse("1X").is_err());
+    assert!(BlockSize::parse("1Kb").is_err());
+    assert!(BlockSize::parse("1Q").unwrap_err().contains("too large"));
+  }
+
+  #[test]
+  fn human_readable_like_gnu_ls() {
+    assert_eq!(sizes(BlockSize::human(true)), ["0", "1", "1000", "1023", "1.0K", "1.5K", "10K", "4.7G"]);
+    assert_eq!(sizes(BlockSize::human(false)), ["0", "1", "1.0k", "1.1k", "1.1k", "1.6k", "11k", "5.0G"]);
+  }
+
+  #[test]
+  fn fixed_units_round_up() {
+    assert_eq!(sizes(BlockSize::parse("K").unwrap()), ["0K", "1K", "1K", "1K", "1K", "2K", "10K", "4882813K"]);
+    assert_eq!(sizes(BlockSize::parse("KB").unwrap()), ["0kB", "1kB", "1kB", "2kB", "2kB", "2kB", "11kB", "5000000kB"]);
+    assert_eq!(sizes(BlockSize::parse("512").unwrap()), ["0", "1", "2", "2", "2", "3", "20", "9765625"]);
+    assert_eq!(BlockSize::parse("'1").unwrap().human_readable(1048576, 1), "1,048,576");
+  }
+
+  #[test]
+  fn converts_from_blocks() {
+    assert_eq!(BlockSize::parse("K").unwrap().human_readable(3, 512), "2K");
+    assert_eq!(BlockSize::human(true).human_readable(8, 512), "4.0K");
+  }
+}
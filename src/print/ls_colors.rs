@@ -0,0 +1,413 @@
+// Standard Libraries
+use std::env::VarError;
+use std::os::linux::fs::MetadataExt;
+
+// Project Modules
+use crate::filesystem::{FileInfo, FileType};
+use super::Error;
+
+// Mode bits, see inode(7)
+const S_IFMT: u32 = 0o170000;
+const S_IFSOCK: u32 = 0o140000;
+const S_IFLNK: u32 = 0o120000;
+const S_IFREG: u32 = 0o100000;
+const S_IFBLK: u32 = 0o060000;
+const S_IFDIR: u32 = 0o040000;
+const S_IFCHR: u32 = 0o020000;
+const S_IFIFO: u32 = 0o010000;
+const S_ISUID: u32 = 0o4000;
+const S_ISGID: u32 = 0o2000;
+const S_ISVTX: u32 = 0o1000;
+const S_IWOTH: u32 = 0o002;
+const S_IXUGO: u32 = 0o111;
+
+/// Keys of LS_COLORS, in the same order as in GNU ls.
+/// Normal, Door, Capability and ClearToEol are accepted, but never applied
+#[allow(dead_code)]
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Indicator {
+  Left = 0,
+  Right,
+  End,
+  Reset,
+  Normal,
+  File,
+  Dir,
+  Link,
+  Fifo,
+  Sock,
+  BlockDev,
+  CharDev,
+  Missing,
+  Orphan,
+  Exec,
+  Door,
+  SetUid,
+  SetGid,
+  Sticky,
+  OtherWritable,
+  StickyOtherWritable,
+  Capability,
+  MultiHardlink,
+  ClearToEol
+}
+
+pub const INDICATOR_NAMES: [&str; 24] = [
+  "lc", "rc", "ec", "rs", "no", "fi", "di", "ln", "pi", "so", "bd", "cd", "mi", "or", "ex",
+  "do", "su", "sg", "st", "ow", "tw", "ca", "mh", "cl"
+];
+
+/// Built-in colors, used for every key LS_COLORS doesn't set
+const DEFAULT_INDICATORS: [Option<&str>; 24] = [
+  Some("\x1b["), Some("m"), None, Some("0"), None, None, Some("01;34"), Some("01;36"),
+  Some("33"), Some("01;35"), Some("01;33"), Some("01;33"), None, None, Some("01;32"),
+  Some("01;35"), Some("37;41"), Some("30;43"), Some("37;44"), Some("34;42"), Some("30;42"),
+  None, None, Some("\x1b[K")
+];
+
+#[derive(Debug)]
+struct Extension {
+  suffix: String,
+  sequence: String,
+  /// Only match case sensitive, if other entries differ from this one only by case
+  exact_match: bool
+}
+
+#[derive(Debug)]
+pub struct LsColors {
+  indicators: Vec<Option<String>>,
+  /// Later entries take precedence
+  extensions: Vec<Extension>,
+  /// "ln=target": color symbolic links like the file they point to
+  pub symlink_as_referent: bool
+}
+
+impl Default for LsColors {
+  fn default() -> Self {
+    LsColors {
+      indicators: DEFAULT_INDICATORS.iter().map(|i| i.map(String::from)).collect(),
+      extensions: Vec::new(),
+      symlink_as_referent: false
+    }
+  }
+}
+
+/// Parses a value or `*`-key of LS_COLORS, resolving escape sequences (\e, \x1b, \033, ^[, ...).
+/// Stops at ':' or at the end, and at '=' if `equals_end` is set.
+/// Returns the unescaped string and the number of consumed bytes, None if malformed.
+fn get_funky_string(input: &[u8], equals_end: bool) -> Option<(String, usize)> {
+  let mut out: Vec<u8> = Vec::new();
+  let mut i: usize = 0;
+
+  while i < input.len() {
+    match input[i] {
+      b':' => break,
+      b'=' if equals_end => break,
+      b'\\' => {
+        i += 1;
+        let c: u8 = *input.get(i)?;
+        match c {
+          b'0'..=b'7' => {
+            let mut num: u32 = 0;
+            while i < input.len() && (b'0'..=b'7').contains(&input[i]) {
+              num = (num << 3) + (input[i] - b'0') as u32;
+              i += 1;
+            }
+            out.push(num as u8);
+            continue;
+          }
+          b'x' | b'X' => {
+            let mut num: u32 = 0;
+            i += 1;
+            while i < input.len() && input[i].is_ascii_hexdigit() {
+              num = (num << 4) + (input[i] as char).to_digit(16).unwrap();
+              i += 1;
+            }
+            out.push(num as u8);
+            continue;
+          }
+          b'a' => out.push(7),
+          b'b' => out.push(8),
+          b'e' => out.push(27),
+          b'f' => out.push(12),
+          b'n' => out.push(b'\n'),
+          b'r' => out.push(b'\r'),
+          b't' => out.push(b'\t'),
+          b'v' => out.push(11),
+          b'?' => out.push(127),
+          b'_' => out.push(b' '),
+          c => out.push(c)
+        }
+      }
+      b'^' => {
+        i += 1;
+        match *input.get(i)? {
+          c @ b'@'..=b'~' => out.push(c & 0x1f),
+          b'?' => out.push(127),
+          _ => return None
+        }
+      }
+      c => out.push(c)
+    }
+    i += 1;
+  }
+
+  Some((String::from_utf8_lossy(&out).into_owned(), i))
+}
+
+impl LsColors {
+  /// Parses the LS_COLORS environment variable. Unset or empty means built-in colors.
+  /// The errors are returned in the order they are reported, if the value is malformed
+  pub fn from_env() -> Result<Self, Vec<Error>> {
+    match std::env::var("LS_COLORS") {
+      Ok(value) => Self::parse(&value),
+      Err(VarError::NotUnicode(_)) => Err(vec![Error::UnparsableColorsError]),
+      Err(VarError::NotPresent) => Ok(Self::default())
+    }
+  }
+
+  pub fn parse(value: &str) -> Result<Self, Vec<Error>> {
+    let unparsable = || vec![Error::UnparsableColorsError];
+    let mut colors: LsColors = LsColors::default();
+    let input: &[u8] = value.as_bytes();
+    let mut i: usize = 0;
+
+    while i < input.len() {
+      match input[i] {
+        b':' => i += 1,
+        b'*' => {
+          let (suffix, len) = get_funky_string(&input[i + 1..], true).ok_or_else(unparsable)?;
+          i += 1 + len;
+          if input.get(i) != Some(&b'=') {
+            return Err(unparsable());
+          }
+          let (sequence, len) = get_funky_string(&input[i + 1..], false).ok_or_else(unparsable)?;
+          i += 1 + len;
+          colors.extensions.push(Extension { suffix, sequence, exact_match: false });
+        }
+        _ => {
+          if input.len() < i + 3 || input[i + 2] != b'=' {
+            return Err(unparsable());
+          }
+          let label: &str = std::str::from_utf8(&input[i..i + 2]).map_err(|_| unparsable())?;
+          let idx: usize = match INDICATOR_NAMES.iter().position(|name| *name == label) {
+            Some(idx) => idx,
+            None => return Err(vec![
+              Error::UnrecognizedPrefixError { prefix: label.into() },
+              Error::UnparsableColorsError
+            ])
+          };
+          let (sequence, len) = get_funky_string(&input[i + 3..], false).ok_or_else(unparsable)?;
+          i += 3 + len;
+          colors.indicators[idx] = Some(sequence);
+        }
+      }
+    }
+
+    if colors.indicators[Indicator::Link as usize].as_deref() == Some("target") {
+      colors.symlink_as_referent = true;
+    }
+    colors.mark_exact_extensions();
+
+    Ok(colors)
+  }
+
+  /// Suffixes are matched case insensitive, unless they only differ in case and color
+  fn mark_exact_extensions(&mut self) {
+    for a in 0..self.extensions.len() {
+      for b in 0..self.extensions.len() {
+        if a != b
+          && self.extensions[a].suffix != self.extensions[b].suffix
+          && self.extensions[a].suffix.eq_ignore_ascii_case(&self.extensions[b].suffix)
+          && self.extensions[a].sequence != self.extensions[b].sequence {
+          self.extensions[a].exact_match = true;
+        }
+      }
+    }
+  }
+
+  fn get(&self, indicator: Indicator) -> Option<&str> {
+    self.indicators[indicator as usize].as_deref()
+  }
+
+  /// Set to something other than nothing, "0" or "00"
+  pub fn is_colored(&self, indicator: Indicator) -> bool {
+    !matches!(self.get(indicator), None | Some("") | Some("0") | Some("00"))
+  }
+
+  /// Whether targets of symbolic links have to be examined for coloring
+  pub fn needs_link_target(&self) -> bool {
+    self.symlink_as_referent || self.is_colored(Indicator::Orphan) || self.is_colored(Indicator::Missing)
+  }
+
+  fn extension_sequence(&self, name: &str) -> Option<&str> {
+    self.extensions.iter().rev().find(|ext| {
+      let len: usize = ext.suffix.len();
+      if len > name.len() || !name.is_char_boundary(name.len() - len) {
+        return false;
+      }
+      let tail: &str = &name[name.len() - len..];
+      if ext.exact_match { tail == ext.suffix } else { tail.eq_ignore_ascii_case(&ext.suffix) }
+    }).map(|ext| ext.sequence.as_str())
+  }
+
+  /// Indicator for a file of the given mode, refined by its permission bits
+  fn indicator_for_mode(&self, mode: u32, nlink: u64) -> Indicator {
+    match mode & S_IFMT {
+      S_IFREG => {
+        if mode & S_ISUID != 0 && self.is_colored(Indicator::SetUid) { Indicator::SetUid }
+        else if mode & S_ISGID != 0 && self.is_colored(Indicator::SetGid) { Indicator::SetGid }
+        else if mode & S_IXUGO != 0 && self.is_colored(Indicator::Exec) { Indicator::Exec }
+        else if nlink > 1 && self.is_colored(Indicator::MultiHardlink) { Indicator::MultiHardlink }
+        else { Indicator::File }
+      }
+      S_IFDIR => {
+        if mode & S_ISVTX != 0 && mode & S_IWOTH != 0 && self.is_colored(Indicator::StickyOtherWritable) {
+          Indicator::StickyOtherWritable
+        }
+        else if mode & S_IWOTH != 0 && self.is_colored(Indicator::OtherWritable) { Indicator::OtherWritable }
+        else if mode & S_ISVTX != 0 && self.is_colored(Indicator::Sticky) { Indicator::Sticky }
+        else { Indicator::Dir }
+      }
+      S_IFLNK => Indicator::Link,
+      S_IFIFO => Indicator::Fifo,
+      S_IFSOCK => Indicator::Sock,
+      S_IFBLK => Indicator::BlockDev,
+      S_IFCHR => Indicator::CharDev,
+      _ => Indicator::Orphan
+    }
+  }
+
+  /// Indicator for files that could not be stat'ed, based on the type read from the directory
+  fn indicator_for_type(file_type: &FileType) -> Indicator {
+    match file_type {
+      FileType::Unknown => Indicator::Orphan,
+      FileType::FIFO => Indicator::Fifo,
+      FileType::CharDev => Indicator::CharDev,
+      FileType::Directory | FileType::ArgDirectory => Indicator::Dir,
+      FileType::BlockDev => Indicator::BlockDev,
+      FileType::Normal => Indicator::File,
+      FileType::SymbolicLink => Indicator::Link,
+      FileType::Sock => Indicator::Sock
+    }
+  }
+
+  /// Color sequence of the file's name, None if it is to be left uncolored
+  pub fn sequence_for(&self, file_info: &FileInfo) -> Option<&str> {
+    let is_link: bool = file_info.file_type == FileType::SymbolicLink;
+    let link_ok: bool = file_info.link_metadata.is_some();
+
+    let mut indicator: Indicator = match &file_info.metadata {
+      None => Self::indicator_for_type(&file_info.file_type),
+      Some(metadata) => {
+        // Links, whose target exists, may be colored like the target
+        let metadata = match &file_info.link_metadata {
+          Some(link_metadata) if self.symlink_as_referent => link_metadata,
+          _ => metadata
+        };
+        self.indicator_for_mode(metadata.st_mode(), metadata.st_nlink())
+      }
+    };
+
+    if indicator == Indicator::File
+      && let Some(sequence) = self.extension_sequence(&file_info.name) {
+      return Some(sequence);
+    }
+
+    if indicator == Indicator::Link && is_link && !link_ok
+      && (self.symlink_as_referent || self.is_colored(Indicator::Orphan)) {
+      indicator = Indicator::Orphan;
+    }
+
+    self.get(indicator)
+  }
+
+  /// Color sequence of a symbolic link's target, mi for targets that don't exist.
+  /// The target's extension is taken from target_name
+  pub fn sequence_for_target(&self, target_name: &str, file_info: &FileInfo) -> Option<&str> {
+    let indicator: Indicator = match &file_info.link_metadata {
+      Some(metadata) => self.indicator_for_mode(metadata.st_mode(), metadata.st_nlink()),
+      None if self.is_colored(Indicator::Missing) => Indicator::Missing,
+      None => Indicator::Orphan
+    };
+
+    if indicator == Indicator::File
+      && let Some(sequence) = self.extension_sequence(target_name) {
+      return Some(sequence);
+    }
+
+    self.get(indicator)
+  }
+
+  /// Writes the name enclosed in the escape sequences of the color
+  pub fn paint(&self, name: String, sequence: &str) -> String {
+    let left: &str = self.get(Indicator::Left).unwrap_or_default();
+    let right: &str = self.get(Indicator::Right).unwrap_or_default();
+
+    let end: String = match self.get(Indicator::End) {
+      Some(end) => end.to_string(),
+      None => format!("{}{}{}", left, self.get(Indicator::Reset).unwrap_or_default(), right)
+    };
+
+    format!("{}{}{}{}{}", left, sequence, right, name, end)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{Error, Indicator, LsColors};
+
+  #[test]
+  fn parse_indicators_over_the_defaults() {
+    let colors: LsColors = LsColors::parse("di=01;31:ex=:rs=0").unwrap();
+    assert_eq!(colors.get(Indicator::Dir), Some("01;31"));
+    assert_eq!(colors.get(Indicator::Link), Some("01;36"));
+    assert!(!colors.is_colored(Indicator::Exec));
+    assert!(!colors.symlink_as_referent);
+    assert!(LsColors::parse("ln=target").unwrap().symlink_as_referent);
+    assert!(LsColors::parse("").is_ok());
+  }
+
+  #[test]
+  fn parse_escape_sequences() {
+    let colors: LsColors = LsColors::parse("lc=\\e[:rc=^[m:ec=\\033\\x41\\:\\^").unwrap();
+    assert_eq!(colors.get(Indicator::Left), Some("\x1b["));
+    assert_eq!(colors.get(Indicator::Right), Some("\x1bm"));
+    assert_eq!(colors.get(Indicator::End), Some("\x1bA:^"));
+  }
+
+  #[test]
+  fn extensions_match_case_insensitive_unless_ambiguous() {
+    let colors: LsColors = LsColors::parse("*.tar=31:*.jpg=32:*.JPG=33:*.gz=34:*.GZ=34").unwrap();
+    assert_eq!(colors.extension_sequence("a.TAR"), Some("31"));
+    assert_eq!(colors.extension_sequence("a.jpg"), Some("32"));
+    assert_eq!(colors.extension_sequence("a.JPG"), Some("33"));
+    assert_eq!(colors.extension_sequence("a.Jpg"), None);
+    assert_eq!(colors.extension_sequence("a.gZ"), Some("34"));
+    assert_eq!(colors.extension_sequence("tar"), None);
+  }
+
+  #[test]
+  fn indicators_follow_the_mode() {
+    let colors: LsColors = LsColors::default();
+    assert_eq!(colors.indicator_for_mode(0o100644, 1), Indicator::File);
+    assert_eq!(colors.indicator_for_mode(0o100755, 1), Indicator::Exec);
+    assert_eq!(colors.indicator_for_mode(0o104755, 1), Indicator::SetUid);
+    assert_eq!(colors.indicator_for_mode(0o041777, 2), Indicator::StickyOtherWritable);
+    assert_eq!(colors.indicator_for_mode(0o040757, 2), Indicator::OtherWritable);
+    assert_eq!(colors.indicator_for_mode(0o010644, 1), Indicator::Fifo);
+  }
+
+  #[test]
+  fn malformed_values_are_reported() {
+    let errors: Vec<Error> = LsColors::parse("xx=01").unwrap_err();
+    assert!(matches!(errors.as_slice(), [
+      Error::UnrecognizedPrefixError { prefix },
+      Error::UnparsableColorsError
+    ] if prefix == "xx"));
+
+    for value in ["di", "*.tar", "*.tar:", "di=^1"] {
+      assert!(matches!(LsColors::parse(value).unwrap_err().as_slice(), [Error::UnparsableColorsError]), "{}", value);
+    }
+  }
+}
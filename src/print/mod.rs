@@ -1,5 +1,11 @@
+mod error;
 pub mod print_info;
 pub mod format_info;
+pub mod format;
+pub mod style;
+pub mod ls_colors;
+pub mod dircolors;
+pub mod human;
 
 // Standard Libraries
 use std::fs::{FileType, Metadata};
@@ -8,39 +14,74 @@ use std::os::unix::fs::FileTypeExt;
 
 // Cargo Modules
 use chrono::{Local, TimeZone, Utc};
+use unicode_width::UnicodeWidthStr;
 
 // Project Modules
-use crate::filesystem::get_major_and_minor;
+use crate::filesystem::{get_major_and_minor, ChainEnd, IdCache, LinkChain, NameStyle, TimeType, ST_NBLOCKSIZE};
+use crate::exit_status::{ExitStatus, IntoExitStatus};
 
 use super::{Rc, FileInfo, WorkingSet};
 use self::format_info::*;
-use self::print_info::*;
-
-const GREEN: &str = "\x1b[1;32m";
-const BLUE:  &str = "\x1b[1;34m";
-const CYAN:  &str = "\x1b[1;36m";
-const RESET: &str = "\x1b[0m";
+use self::format::*;
+pub use self::error::*;
+use self::style::Style;
+use self::human::BlockSize;
 
+/// Pads from column `from` to `to`, with tabs if a tab size is set
+fn indent(mut from: usize, to: usize, tabsize: usize) {
+  while from < to {
+    if tabsize != 0 && to / tabsize > (from + 1) / tabsize {
+      print!("\t");
+      from += tabsize - from % tabsize;
+    } else {
+      print!(" ");
+      from += 1;
+    }
+  }
+}
 
-// Executable Permissions for owner, group and other
-//const S_IXUSR: u32 = 0o100;
-//const S_IXGRP: u32 = 0o010;
-//const S_IXOTH: u32 = 0o001;
-// S_IXUGO = (S_IXUSR | S_IXGRP | S_IXOTH)
-const S_IXUGO: u32 = 0o111;  
+/// Columns printed in front of the name in every format but -l
+fn frills_to_string(f: &FileInfo, working_set: &WorkingSet) -> String {
+  let mut out: String = String::new();
+  // Names separated by commas are not aligned
+  let unaligned: FormatInfo = FormatInfo::new();
+  let format_info: &FormatInfo = {
+    if working_set.format == Format::WithCommas { &unaligned }
+    else { working_set.format_info.as_ref().unwrap_or(&unaligned) }
+  };
 
-fn indent(mut from: usize, to: usize) {
-  while from < to {
-    print!(" ");
-    from += 1;
+  if working_set.print_inode {
+    out.push_str(&inode_to_string(f.inode, format_info));
+    out.push(' ');
   }
+  if working_set.print_block_size {
+    out.push_str(&block_size_to_string(f.metadata.as_ref(), format_info, &working_set.block_size));
+    out.push(' ');
+  }
+
+  out
+}
+
+/// Screen width of the name and the columns printed in front of it
+pub fn file_width(f: &FileInfo, working_set: &WorkingSet) -> usize {
+  f.width + UnicodeWidthStr::width(frills_to_string(f, working_set).as_str())
 }
 
 pub fn print_current_files(working_set: &mut WorkingSet) {
-  if working_set.args.long {
-    print_long_format(working_set);
-  } else {
-    print_many_per_line(working_set);
+  match working_set.format {
+    Format::Long => print_long_format(working_set),
+    Format::OnePerLine => print_one_per_line(working_set),
+    Format::ManyPerLine => print_many_per_line(working_set),
+    Format::Horizontal => print_horizontal(working_set),
+    Format::WithCommas => print_with_commas(working_set)
+  }
+}
+
+fn print_one_per_line(working_set: &WorkingSet) {
+  for entry in working_set.sorted_files.iter() {
+    let f: Rc<FileInfo> = entry.upgrade().unwrap();
+
+    println!("{}{}", frills_to_string(&f, working_set), file_name_to_string(&f, working_set.style.as_ref()));
   }
 }
 
@@ -49,11 +90,8 @@ pub fn print_current_files(working_set: &mut WorkingSet) {
 fn print_many_per_line(working_set: &mut WorkingSet) {
   let file_count: usize = working_set.sorted_files.len();
   let columns: usize = working_set.calculate_columns(true);
-  let rows: usize = file_count / columns + ((file_count % columns != 0) as usize);
-  
-  // Panics if column_state is None, which should never happen, as it is only initialized, 
-  // when working_set.sorted_files are supposed to be printed  
-  let line_fmt: &ColumnInfo = &working_set.column_state.as_ref().unwrap().columns[columns-1];
+  let rows: usize = file_count / columns + (!file_count.is_multiple_of(columns) as usize);
+  let line_fmt: &Vec<usize> = &working_set.column_state.line_fmt;
 
   for row in 0..rows {
     let mut column: usize = 0; 
@@ -64,24 +102,81 @@ fn print_many_per_line(working_set: &mut WorkingSet) {
       // If upgrade() or unwrap() fail, then because the referenced object fell out of scope,
       // which shouldn't have happened in the first place at this point
       let f: &Rc<FileInfo> = &working_set.sorted_files[filesno].upgrade().unwrap();
-      let name_length: usize = f.width;
-      let max_name_length: usize = line_fmt.col_arr[column];
+      let name_length: usize = file_width(f, working_set);
+      let max_name_length: usize = line_fmt[column];
       column += 1;
 
-      print!("{}", quoted_name_to_string(f.quoted_name(), f.metadata.as_ref(), &f.file_type));
+      print!("{}{}", frills_to_string(f, working_set), file_name_to_string(f, working_set.style.as_ref()));
 
       if file_count - rows <= filesno {
         break;
       }
       filesno += rows;
 
-      indent(pos + name_length, pos + max_name_length);
+      indent(pos + name_length, pos + max_name_length, working_set.print_info.tabsize);
       pos += max_name_length
     }
     println!();
   }
 }
 
+// Same as print_many_per_line(), but files are sorted along rows instead of columns
+fn print_horizontal(working_set: &mut WorkingSet) {
+  let file_count: usize = working_set.sorted_files.len();
+  let columns: usize = working_set.calculate_columns(false);
+  let line_fmt: &Vec<usize> = &working_set.column_state.line_fmt;
+  let mut pos: usize = 0;
+
+  let f: &Rc<FileInfo> = &working_set.sorted_files[0].upgrade().unwrap();
+  let mut name_length: usize = file_width(f, working_set);
+  let mut max_name_length: usize = line_fmt[0];
+  print!("{}{}", frills_to_string(f, working_set), file_name_to_string(f, working_set.style.as_ref()));
+
+  for filesno in 1..file_count {
+    let column: usize = filesno % columns;
+
+    if column == 0 {
+      println!();
+      pos = 0;
+    } else {
+      indent(pos + name_length, pos + max_name_length, working_set.print_info.tabsize);
+      pos += max_name_length;
+    }
+
+    let f: &Rc<FileInfo> = &working_set.sorted_files[filesno].upgrade().unwrap();
+    print!("{}{}", frills_to_string(f, working_set), file_name_to_string(f, working_set.style.as_ref()));
+
+    name_length = file_width(f, working_set);
+    max_name_length = line_fmt[column];
+  }
+  println!();
+}
+
+fn print_with_commas(working_set: &WorkingSet) {
+  let line_length: usize = working_set.print_info.line_length;
+  let mut pos: usize = 0;
+
+  for (filesno, entry) in working_set.sorted_files.iter().enumerate() {
+    let f: Rc<FileInfo> = entry.upgrade().unwrap();
+    let width: usize = file_width(&f, working_set);
+
+    if filesno != 0 {
+      // Wrap the line, if the next name and its separator don't fit
+      if pos + width + 2 < line_length {
+        print!(", ");
+        pos += 2;
+      } else {
+        println!(",");
+        pos = 0;
+      }
+    }
+
+    print!("{}{}", frills_to_string(&f, working_set), file_name_to_string(&f, working_set.style.as_ref()));
+    pos += width;
+  }
+  println!();
+}
+
 fn print_long_format(working_set: &WorkingSet) {
   for entry in working_set.sorted_files.iter() {
     let mut output = String::new();
@@ -94,6 +189,18 @@ fn print_long_format(working_set: &WorkingSet) {
     let metadata: Option<&Metadata> = f.metadata.as_ref();
     let file_type: &super::FileType = &f.file_type; 
 
+    // Inode
+    if working_set.print_inode {
+      output.push_str(&inode_to_string(f.inode, format_info));
+      output.push(' ');
+    }
+
+    // Allocated size
+    if working_set.print_block_size {
+      output.push_str(&block_size_to_string(metadata, format_info, &working_set.block_size));
+      output.push(' ');
+    }
+
     // Type and Permissions
     let mode = permissions_to_string(metadata, file_type);
     output.push_str(&mode);
@@ -105,32 +212,50 @@ fn print_long_format(working_set: &WorkingSet) {
     output.push(' ');
 
     // Owner
-    let owner = owner_to_string(metadata, format_info);
-    output.push_str(&owner);
-    output.push(' ');
+    if working_set.print_owner {
+      let owner = owner_to_string(metadata, format_info, &working_set.id_cache);
+      output.push_str(&owner);
+      output.push(' ');
+    }
 
     // Group
-    let group = group_to_string(metadata, format_info);
-    output.push_str(&group);
-    output.push(' ');
+    if working_set.print_group {
+      let group = group_to_string(metadata, format_info, &working_set.id_cache);
+      output.push_str(&group);
+      output.push(' ');
+    }
+
+    // Author, which is always the owner on Linux
+    if working_set.print_author {
+      let author = owner_to_string(metadata, format_info, &working_set.id_cache);
+      output.push_str(&author);
+      output.push(' ');
+    }
 
     // Size or dev/inode
-    let size = file_size_to_string(metadata, format_info);
+    let size = file_size_to_string(metadata, format_info, &working_set.file_block_size);
     output.push_str(&size);
     output.push(' ');
 
     // Timestamp
-    let timestamp = time_stamp_to_string(metadata);
+    let timestamp = time_stamp_to_string(metadata, working_set.time_type);
     output.push_str(&timestamp);
     output.push(' ');
 
-    // File name
-    output.push_str(&quoted_name_to_string(f.quoted_name(), metadata, file_type));
+    // File name, links get the indicator of their target instead
+    if f.file_type == super::FileType::SymbolicLink {
+      output.push_str(&quoted_name_to_string(&f, working_set.style.as_ref()));
+    } else {
+      output.push_str(&file_name_to_string(&f, working_set.style.as_ref()));
+    }
     output.push(' ');
 
     // Target if symbolic link
-    if f.file_type == super::FileType::SymbolicLink {
-      output.push_str(&symlink_path_to_string(&f));
+    if let Some(chain) = &f.link_chain {
+      output.push_str(&link_chain_to_string(&f, chain, working_set.name_style, working_set.style.as_ref()));
+    }
+    else if f.file_type == super::FileType::SymbolicLink {
+      output.push_str(&symlink_path_to_string(&f, working_set.name_style, working_set.style.as_ref()));
     }
 
     println!("{}", output);
@@ -189,61 +314,74 @@ fn n_link_to_string(metadata: Option<&Metadata>, format_info: &FormatInfo) -> St
   )
 }
 
-fn owner_to_string(metadata:  Option<&Metadata>, format_info: &FormatInfo) -> String {
-  let out: String = match metadata {
-    Some(metadata) => metadata.st_uid().to_string(),
-    None => String::from("?")
-  };
+/// Names are aligned left, numeric IDs right, like in GNU ls
+fn user_or_group_to_string(name: Option<&str>, id: Option<u32>, width: usize) -> String {
+  match (name, id) {
+    (Some(name), _) => {
+      let name_width: usize = UnicodeWidthStr::width(name);
+      format!("{}{}", name, " ".repeat(width.saturating_sub(name_width)))
+    },
+    (None, Some(id)) => format!("{:>1$}", id, width),
+    (None, None) => format!("{:>1$}", "?", width)
+  }
+}
 
-  format!(
-    "{:>1$}", 
-    out, 
-    format_info.user_length
-  )
+fn owner_to_string(metadata:  Option<&Metadata>, format_info: &FormatInfo, id_cache: &IdCache) -> String {
+  let uid: Option<u32> = metadata.map(|m| m.st_uid());
+
+  user_or_group_to_string(uid.and_then(|uid| id_cache.user_name(uid)), uid, format_info.user_length)
 }
 
-fn group_to_string(metadata:  Option<&Metadata>, format_info: &FormatInfo) -> String {
-  let out: String = match metadata {
-    Some(metadata) => metadata.st_gid().to_string(),
-    None => String::from("?")
-  };
+fn group_to_string(metadata:  Option<&Metadata>, format_info: &FormatInfo, id_cache: &IdCache) -> String {
+  let gid: Option<u32> = metadata.map(|m| m.st_gid());
 
-  format!(
-    "{:>1$}", 
-    out, 
-    format_info.group_length
-  )
+  user_or_group_to_string(gid.and_then(|gid| id_cache.group_name(gid)), gid, format_info.group_length)
 }
 
 /// Writes file size into a string
-fn file_size_to_string(metadata: Option<&Metadata>, format_info: &FormatInfo) -> String {
+fn file_size_to_string(metadata: Option<&Metadata>, format_info: &FormatInfo, block_size: &BlockSize) -> String {
   match metadata {
     Some(metadata) => {
       let file_type: FileType = metadata.file_type();
       
       if file_type.is_block_device() || file_type.is_char_device() { 
-        let (major, minor): (u64, u64) = get_major_and_minor(metadata.st_dev());
+        let (major, minor): (u64, u64) = get_major_and_minor(metadata.st_rdev());
         
         format!("{:>major_length$}, {:>minor_length$}", major, minor, 
           major_length = format_info.major_length, minor_length = format_info.minor_length)
       } else {
-        format!("{:>1$}", metadata.st_size(), format_info.file_size_length)
+        format!("{:>1$}", block_size.human_readable(metadata.st_size(), 1), format_info.file_size_length)
       }
     },
     None =>  format!("{:>1$}", "?", format_info.file_size_length)
   }
 }
 
-/// Writes time stamp into a string
-fn time_stamp_to_string(metadata: Option<&Metadata>) -> String {
-  const EMPTY: &str = "                  ?";
+/// Writes the inode number into a string, "?" if unknown
+fn inode_to_string(inode: u64, format_info: &FormatInfo) -> String {
+  if inode == 0 {
+    format!("{:>1$}", "?", format_info.inode_length)
+  } else {
+    format!("{:>1$}", inode, format_info.inode_length)
+  }
+}
 
+/// Writes the allocated size into a string
+fn block_size_to_string(metadata: Option<&Metadata>, format_info: &FormatInfo, block_size: &BlockSize) -> String {
   match metadata {
-    Some(metadata) => {
-      let mtime: i64 = metadata.st_mtime();
+    Some(metadata) => format!("{:>1$}", block_size.human_readable(metadata.st_blocks(), ST_NBLOCKSIZE), format_info.block_length),
+    None => format!("{:>1$}", "?", format_info.block_length)
+  }
+}
+
+/// Writes time stamp into a string
+fn time_stamp_to_string(metadata: Option<&Metadata>, time_type: TimeType) -> String {
+  const EMPTY: &str = "                  ?";
 
+  match metadata.and_then(|m| time_type.timestamp(m)) {
+    Some((time, _)) => {
       // Calculate utc, unwrap it, convert to local time and format it. Else return "?"
-      Utc.timestamp_opt(mtime, 0)
+      Utc.timestamp_opt(time, 0)
       .single()
       .map(|dt_utc| dt_utc.with_timezone(&Local))
       .map(|dt_local| dt_local.format("%Y-%m-%d %H:%M:%S").to_string())
@@ -253,27 +391,66 @@ fn time_stamp_to_string(metadata: Option<&Metadata>) -> String {
   }
 }
 
-fn quoted_name_to_string(name: String, metadata:  Option<&Metadata>, file_type: &super::FileType) -> String {
-    match file_type {
-    super::FileType::Directory | 
-    super::FileType::ArgDirectory => format!("{}{}{}", BLUE, name, RESET),
-    super::FileType::SymbolicLink => format!("{}{}{}", CYAN, name, RESET),
-    _ => if is_executable(metadata) { format!("{}{}{}", GREEN, name, RESET) } else { name }
+fn quoted_name_to_string(file_info: &FileInfo, style: &dyn Style) -> String {
+  style.paint(file_info.quoted_name(), file_info)
+}
+
+/// Writes the painted name and its type indicator, which is never colored
+fn file_name_to_string(file_info: &FileInfo, style: &dyn Style) -> String {
+  let mut out: String = quoted_name_to_string(file_info, style);
+
+  if let Some(indicator) = file_info.indicator {
+    out.push(indicator);
   }
+
+  out
 }
 
-fn is_executable(metadata: Option<&Metadata>) -> bool{
-  match metadata {
-    //Some(metadata) => metadata.st_mode() & (S_IXUSR | S_IXGRP | S_IXOTH) != 0
-    Some(metadata) => metadata.st_mode() & S_IXUGO != 0,
-    None => false
+/// Writes every hop of a symbolic link (--link-chain). Only the last one is colored by the
+/// final target, followed by how the chain ends, if not resolved
+fn link_chain_to_string(file_info: &FileInfo, chain: &LinkChain, name_style: NameStyle, style: &dyn Style) -> String {
+  let mut out: String = String::new();
+
+  for (idx, hop) in chain.hops.iter().enumerate() {
+    if idx > 0 {
+      out.push(' ');
+    }
+    out.push_str(if hop.absolute { "=> " } else { "-> " });
+
+    let target: String = name_style.quote(&hop.target);
+    if idx + 1 == chain.hops.len() {
+      out.push_str(&style.paint_target(target, &hop.target, file_info));
+    } else {
+      out.push_str(&target);
+    }
+  }
+
+  if chain.end == ChainEnd::Resolved
+    && let Some(indicator) = file_info.link_metadata.as_ref()
+      .and_then(|metadata| name_style.indicator_style.indicator(metadata)) {
+    out.push(indicator);
+  }
+
+  match &chain.end {
+    ChainEnd::Resolved => (),
+    ChainEnd::Loop => out.push_str(" [loop]"),
+    ChainEnd::Missing(component) => out.push_str(&format!(" [missing: {}]", name_style.quote(component))),
+    ChainEnd::Unreadable(message) => out.push_str(&format!(" [unreadable: {}]", message))
   }
+
+  out
 }
 
-/// Writes path of symlink target into a string
-fn symlink_path_to_string(file_info: &FileInfo) -> String {
+/// Writes path of symlink target into a string, colored by and followed by the indicator of
+/// the target's type
+fn symlink_path_to_string(file_info: &FileInfo, name_style: NameStyle, style: &dyn Style) -> String {
   match file_info.link_name.as_ref() {
-    Some(target) => format!("-> {}", target),
+    Some(target) => {
+      let indicator: Option<char> = file_info.link_metadata.as_ref()
+        .and_then(|metadata| name_style.indicator_style.indicator(metadata));
+      let target: String = style.paint_target(name_style.quote(target), target, file_info);
+      format!("-> {}{}", target, indicator.map(String::from).unwrap_or_default())
+    },
     None => "".into()
   }
 }
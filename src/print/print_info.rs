@@ -1,25 +1,54 @@
+// Standard Libraries
+use std::io::{stdout, IsTerminal};
+
 // Cargo Modules
 use terminal_size::{terminal_size, Width};
 
-const MIN_COLUMN_WIDTH: usize = 3;
+pub const MIN_COLUMN_WIDTH: usize = 3;
 
 #[derive(Debug)]
 pub struct PrintInfo {
   pub max_idx: usize,
+  /// Output width, usize::MAX if unlimited
   pub line_length: usize,
+  /// Distance of tab stops used for padding, 0 for spaces only
+  pub tabsize: usize,
+  /// Whether stdout is a terminal, as opposed to a pipe or file
+  pub is_tty: bool,
 }
 
 impl PrintInfo {
-  pub fn new() -> Self {
-    let line_length: usize = match Self::terminal_width() {
-      Some(len) => len,
-      None => 80
-    };
-    let max_idx: usize = line_length / MIN_COLUMN_WIDTH;
+  /// The width is taken from -w, the terminal, COLUMNS and finally defaults to 80.
+  /// The tab size is taken from -T, then TABSIZE
+  pub fn new(width: Option<usize>, tabsize: Option<usize>) -> Self {
+    let line_length: usize = width
+      .or_else(Self::terminal_width)
+      .or_else(|| Self::env_number("COLUMNS", "width"))
+      .map(|w| if w == 0 { usize::MAX } else { w })
+      .unwrap_or(80);
+    let max_idx: usize = (line_length / MIN_COLUMN_WIDTH).max(1);
+    let tabsize: usize = tabsize
+      .or_else(|| Self::env_number("TABSIZE", "tab size"))
+      .unwrap_or(0);
     
     Self {
       max_idx,
-      line_length
+      line_length,
+      tabsize,
+      is_tty: stdout().is_terminal()
+    }
+  }
+
+  /// Reads a number from the environment, warning about invalid values
+  fn env_number(var: &str, what: &str) -> Option<usize> {
+    let value: String = std::env::var(var).ok().filter(|v| !v.is_empty())?;
+
+    match value.parse::<usize>() {
+      Ok(n) => Some(n),
+      Err(_) => {
+        eprintln!("lrs: ignoring invalid {} in environment variable {}: '{}'", what, var, value);
+        None
+      }
     }
   }
 
@@ -39,29 +68,62 @@ pub struct ColumnInfo {
   pub col_arr: Vec<usize>
 }
 
-#[derive(Debug)]
+/// Layouts of 1 to n columns, kept for the whole run. Like the static column_info array
+/// of coreutils, it only ever grows, so directories of similar size don't allocate again
+#[derive(Debug, Default)]
 pub struct ColumnState {
-  pub columns: Vec<ColumnInfo>
+  pub columns: Vec<ColumnInfo>,
+  /// Column widths of the chosen layout
+  pub line_fmt: Vec<usize>
 }
 
 impl ColumnState {
-  /// Does not implement dynamic allocation for changing terminal size!
-  pub fn init_column_info(max_cols: usize) -> Self {
-    let mut columns: Vec<ColumnInfo> = Vec::with_capacity(max_cols);
+  pub fn new() -> Self {
+    ColumnState::default()
+  }
 
-    for i in 0..max_cols {
+  /// Resets the layouts of up to max_cols columns, allocating only those never used before
+  pub fn init_column_info(&mut self, max_cols: usize) {
+    while self.columns.len() < max_cols {
       // i + 1 Spalten
-      let col_count: usize = i + 1;
-      let col_arr: Vec<usize> = vec![MIN_COLUMN_WIDTH; col_count];
-      let line_len: usize = col_count * MIN_COLUMN_WIDTH;
-
-      columns.push(ColumnInfo {
-        col_arr,
-        line_len,
+      let col_count: usize = self.columns.len() + 1;
+      self.columns.push(ColumnInfo {
+        col_arr: vec![MIN_COLUMN_WIDTH; col_count],
+        line_len: col_count * MIN_COLUMN_WIDTH,
         valid_len: true,
       });
     }
 
-    ColumnState { columns }
+    for (i, column) in self.columns.iter_mut().take(max_cols).enumerate() {
+      column.valid_len = true;
+      column.line_len = (i + 1) * MIN_COLUMN_WIDTH;
+      column.col_arr.fill(MIN_COLUMN_WIDTH);
+    }
+  }
+
+  /// Upper bound of columns that could fit into line_length. A layout of c columns fills
+  /// k of them, each at least as wide as a different file, so it is never shorter than
+  /// the k narrowest files next to each other, plus the empty columns.
+  pub fn max_feasible_columns(widths: &[usize], max_cols: usize, line_length: usize, by_columns: bool) -> usize {
+    let file_count: usize = widths.len();
+    let mut sorted: Vec<usize> = widths.iter().map(|w| (w + 2).max(MIN_COLUMN_WIDTH)).collect();
+    sorted.sort_unstable();
+
+    let mut prefix_sums: Vec<usize> = Vec::with_capacity(max_cols + 1);
+    prefix_sums.push(0);
+    for width in sorted.iter().take(max_cols) {
+      prefix_sums.push(prefix_sums.last().unwrap() + width);
+    }
+
+    (1..=max_cols).rev().find(|&cols| {
+      let filled: usize = if by_columns {
+        let rows: usize = file_count.div_ceil(cols);
+        file_count.div_ceil(rows)
+      } else {
+        cols
+      };
+      let min_line_len: usize = prefix_sums[filled].saturating_sub(2) + (cols - filled) * MIN_COLUMN_WIDTH;
+      min_line_len < line_length
+    }).unwrap_or(1)
   }
 }
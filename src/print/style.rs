@@ -0,0 +1,88 @@
+// Standard Libraries
+use std::fmt::Debug;
+
+// Project Modules
+use crate::args::When;
+use crate::filesystem::FileInfo;
+use super::ls_colors::LsColors;
+
+/// SGR parameter of faint text, added to the color of entries ignored by git
+const DIM: &str = "2";
+
+/// Decorates file names on output
+pub trait Style: Debug {
+  /// Returns the (already quoted) name, decorated according to the type of the file
+  fn paint(&self, name: String, file_info: &FileInfo) -> String;
+
+  /// Returns the (already quoted) target of a symbolic link, decorated according to its type.
+  /// The extension is taken from target_name, the unquoted target
+  fn paint_target(&self, target: String, _target_name: &str, _file_info: &FileInfo) -> String {
+    target
+  }
+
+  /// Whether the metadata of symbolic link targets is needed for decoration
+  fn needs_link_target(&self) -> bool {
+    false
+  }
+}
+
+/// Leaves names untouched, therefore never outputs escape sequences
+#[derive(Debug)]
+pub struct Plain;
+
+impl Style for Plain {
+  fn paint(&self, name: String, _file_info: &FileInfo) -> String {
+    name
+  }
+}
+
+/// Colors names by file type, permissions and extension, as configured by LS_COLORS
+#[derive(Debug)]
+pub struct Colored {
+  colors: LsColors
+}
+
+impl Colored {
+  pub fn new(colors: LsColors) -> Self {
+    Colored { colors }
+  }
+}
+
+impl Style for Colored {
+  fn paint(&self, name: String, file_info: &FileInfo) -> String {
+    match (self.colors.sequence_for(file_info), file_info.dimmed) {
+      (Some(sequence), true) => self.colors.paint(name, &format!("{};{}", sequence, DIM)),
+      (None, true) => self.colors.paint(name, DIM),
+      (Some(sequence), false) => self.colors.paint(name, sequence),
+      (None, false) => name
+    }
+  }
+
+  fn paint_target(&self, target: String, target_name: &str, file_info: &FileInfo) -> String {
+    match self.colors.sequence_for_target(target_name, file_info) {
+      Some(sequence) => self.colors.paint(target, sequence),
+      None => target
+    }
+  }
+
+  fn needs_link_target(&self) -> bool {
+    self.colors.needs_link_target()
+  }
+}
+
+/// Whether colors are enabled for the given --color argument and environment.
+/// Precedence: --color, CLICOLOR_FORCE, NO_COLOR, CLICOLOR, and finally whether stdout is a terminal
+pub fn use_color(when: Option<When>, is_tty: bool) -> bool {
+  let env_set = |key: &str| std::env::var_os(key).is_some_and(|v| !v.is_empty());
+  let env_zero = |key: &str| std::env::var_os(key).is_some_and(|v| v == "0");
+
+  match when {
+    Some(When::Always) => true,
+    Some(When::Never) => false,
+    Some(When::Auto) => is_tty,
+    None if env_set("CLICOLOR_FORCE") && !env_zero("CLICOLOR_FORCE") => true,
+    None if env_set("NO_COLOR") => false,
+    None if env_zero("CLICOLOR") => false,
+    None => is_tty
+  }
+}
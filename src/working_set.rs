@@ -1,11 +1,15 @@
 // Standard Libraries
+use std::collections::HashMap;
 use std::rc::{Rc, Weak};
 
+// Cargo Modules
+use clap::ValueEnum;
+
 // Project Modules
-use super::Args; 
+use super::args::{Args, FormatWord, GitIgnoreWord, IndicatorWord, QuotingWord, SortWord, TimeWord, When}; 
 use super::ExitStatus;
 use super::filesystem::*;
-use crate::print::{print_info::*, format_info::*};
+use crate::print::{file_width, print_info::*, format_info::*, format::*, style::*, ls_colors::LsColors, human::BlockSize};
 use crate::ignore_mode::*;
 
 use super::{Arc, AtomicBool};
@@ -25,33 +29,243 @@ pub struct WorkingSet {
   /// Data for output setup
   pub print_info: PrintInfo,
   /// Contains Calculations for column-style output.
-  /// Is recalculated for each directory, unlike in the original coreutils-version, which uses
-  /// a static Array that is assigned more memory when needed. Therefore performance heavy.
-  pub column_state: Option<ColumnState>,
+  /// Like the static array of coreutils, its layouts only grow and are reset for each directory
+  pub column_state: ColumnState,
   /// Exit Status Value: 0 => Success, 1 => Minor Problem, 2 => Critical Problem
   pub exit_status: ExitStatus, 
   /// Mode set, to ignore certain Files
   ignore_mode: IgnoreMode,
-  /// Contains max width of metadata to be printed
+  /// Patterns of --ignore and -B
+  ignore_patterns: Vec<Glob>,
+  /// Patterns of --hide, only applied without -a and -A
+  hide_patterns: Vec<Glob>,
+  /// Whether entries ignored by git are hidden or dimmed (--git-ignore)
+  pub git_ignore_mode: Option<GitIgnoreMode>,
+  /// Ignore files read so far
+  git_ignore: GitIgnore,
+  /// Rules of the directory, whose entries are read
+  git_ignore_dir: Option<Rc<IgnoreDir>>,
+  /// Output format of the file listing
+  pub format: Format,
+  /// Decoration of file names, e.g. colors
+  pub style: Box<dyn Style>,
+  /// How file names are quoted on output and which indicators follow them
+  pub name_style: NameStyle,
+  /// Order in which files are output
+  pub sort_type: SortType,
+  /// Timestamp to be printed and sorted by
+  pub time_type: TimeType,
+  /// Which symbolic links are followed (-L, -H)
+  pub dereference: Dereference,
+  /// Contains max width of metadata to be printed, only set with -l, -i or -s
   pub format_info: Option<FormatInfo>,
+  /// User and group names for the long format
+  pub id_cache: IdCache,
+  /// Unit of block counts, like the total of -l
+  pub block_size: BlockSize,
+  /// Unit of file sizes
+  pub file_block_size: BlockSize,
+  /// Inode number in front of names (-i)
+  pub print_inode: bool,
+  /// Allocated size in front of names (-s)
+  pub print_block_size: bool,
+  /// Columns of the long format, that may be left out
+  pub print_owner: bool,
+  pub print_group: bool,
+  pub print_author: bool,
   /// SIGINT flag
   pub abort_flag: Option<Arc<AtomicBool>>
 }
 
 impl WorkingSet {
-  pub fn new(args: Args, abort_flag: Option<Arc<AtomicBool>>) -> Self {
+  pub fn new(mut args: Args, abort_flag: Option<Arc<AtomicBool>>) -> Self {
+    // Like in GNU ls, -d wins over -R regardless of their order
+    if args.directory {
+      args.recursive = false;
+    }
     let ignore_mode: IgnoreMode = {
-      if args.all { IgnoreMode::IgnoreMinimal }
+      if args.all || args.unsorted_all { IgnoreMode::IgnoreMinimal }
       else if args.almost_all { IgnoreMode::IgnoreDotAndDotDot }
       else { IgnoreMode::IgnoreDefault }
     };
+    let mut ignore_patterns: Vec<Glob> = args.ignore.iter().map(|pattern| Glob::new(pattern)).collect();
+    if args.ignore_backups {
+      ignore_patterns.push(Glob::new("*~"));
+      ignore_patterns.push(Glob::new(".*~"));
+    }
+    let hide_patterns: Vec<Glob> = args.hide.iter().map(|pattern| Glob::new(pattern)).collect();
+    let git_ignore_mode: Option<GitIgnoreMode> = match args.git_ignore {
+      Some(GitIgnoreWord::Hide) => Some(GitIgnoreMode::Hide),
+      Some(GitIgnoreWord::Dim) => Some(GitIgnoreMode::Dim),
+      None => None
+    };
+    let print_info: PrintInfo = PrintInfo::new(args.width, args.tabsize);
+    // Defaults for output into pipes or files, like GNU ls
+    let format: Format = {
+      // -n, -g and -o imply -l and override the other formats like it
+      if args.long || args.numeric_uid_gid || args.long_without_owner || args.long_without_group { Format::Long }
+      else if args.one_per_line { Format::OnePerLine }
+      else if args.columns { Format::ManyPerLine }
+      else if args.horizontal { Format::Horizontal }
+      else if args.commas { Format::WithCommas }
+      else {
+        match args.format {
+          Some(FormatWord::Long) => Format::Long,
+          Some(FormatWord::SingleColumn) => Format::OnePerLine,
+          Some(FormatWord::Across) => Format::Horizontal,
+          Some(FormatWord::Commas) => Format::WithCommas,
+          Some(FormatWord::Vertical) => Format::ManyPerLine,
+          None if print_info.is_tty => Format::ManyPerLine,
+          None => Format::OnePerLine
+        }
+      }
+    };
+    let style: Box<dyn Style> = {
+      if use_color(args.color, print_info.is_tty) {
+        match LsColors::from_env() {
+          Ok(colors) => Box::new(Colored::new(colors)),
+          Err(errors) => {
+            for error in errors {
+              eprintln!("lrs: {}", error);
+            }
+            Box::new(Plain)
+          }
+        }
+      }
+      else { Box::new(Plain) }
+    };
+    let quoting_word: Option<QuotingWord> = {
+      if args.quote_name { Some(QuotingWord::C) }
+      else if args.literal { Some(QuotingWord::Literal) }
+      else if args.escape { Some(QuotingWord::Escape) }
+      else if args.quoting_style.is_some() { args.quoting_style }
+      else {
+        match std::env::var("QUOTING_STYLE") {
+          Ok(value) => match QuotingWord::from_str(&value, false) {
+            Ok(word) => Some(word),
+            Err(_) => {
+              eprintln!("lrs: ignoring invalid value of environment variable QUOTING_STYLE: '{}'", value);
+              None
+            }
+          },
+          Err(_) => None
+        }
+      }
+    };
+    let quoting_style: QuotingStyle = match quoting_word {
+      Some(QuotingWord::Literal) => QuotingStyle::Literal,
+      Some(QuotingWord::Locale) => QuotingStyle::Locale,
+      Some(QuotingWord::Shell) => QuotingStyle::Shell,
+      Some(QuotingWord::ShellAlways) => QuotingStyle::ShellAlways,
+      Some(QuotingWord::ShellEscape) => QuotingStyle::ShellEscape,
+      Some(QuotingWord::ShellEscapeAlways) => QuotingStyle::ShellEscapeAlways,
+      Some(QuotingWord::C) => QuotingStyle::C,
+      Some(QuotingWord::Escape) => QuotingStyle::Escape,
+      Some(QuotingWord::Clocale) => QuotingStyle::CLocale,
+      None if print_info.is_tty => QuotingStyle::ShellEscape,
+      None => QuotingStyle::Literal
+    };
+    let indicator_style: IndicatorStyle = {
+      if let Some(when) = args.classify {
+        match when {
+          When::Always => IndicatorStyle::Classify,
+          When::Auto if print_info.is_tty => IndicatorStyle::Classify,
+          When::Auto | When::Never => IndicatorStyle::None
+        }
+      }
+      else if args.slash { IndicatorStyle::Slash }
+      else if args.file_type { IndicatorStyle::FileType }
+      else {
+        match args.indicator_style {
+          Some(IndicatorWord::Slash) => IndicatorStyle::Slash,
+          Some(IndicatorWord::FileType) => IndicatorStyle::FileType,
+          Some(IndicatorWord::Classify) => IndicatorStyle::Classify,
+          Some(IndicatorWord::None) | None => IndicatorStyle::None
+        }
+      }
+    };
+    let hide_control_chars: bool = {
+      if args.hide_control_chars { true }
+      else if args.show_control_chars { false }
+      else { print_info.is_tty }
+    };
+    let name_style: NameStyle = NameStyle {
+      quoting_style,
+      indicator_style,
+      hide_control_chars,
+      reveal_invisible: args.reveal_invisible
+    };
+    // Without -L, -H or --dereference-command-line-symlink-to-dir, links are only
+    // followed if neither -d, -F nor -l is given
+    let dereference: Dereference = {
+      if args.dereference { Dereference::Always }
+      else if args.dereference_command_line { Dereference::CommandLineArguments }
+      else if args.dereference_command_line_symlink_to_dir { Dereference::CommandLineSymlinkToDir }
+      else if args.directory || indicator_style == IndicatorStyle::Classify || format == Format::Long {
+        Dereference::Never
+      }
+      else { Dereference::CommandLineSymlinkToDir }
+    };
+    let time_type: TimeType = {
+      if args.atime { TimeType::Access }
+      else if args.ctime { TimeType::Change }
+      else {
+        match args.time {
+          Some(TimeWord::Atime) => TimeType::Access,
+          Some(TimeWord::Ctime) => TimeType::Change,
+          Some(TimeWord::Birth) => TimeType::Birth,
+          Some(TimeWord::Mtime) | None => TimeType::Modification
+        }
+      }
+    };
+    let sort_type: SortType = {
+      if args.unsorted_all || args.unsorted { SortType::None }
+      else if args.sort_size { SortType::Size }
+      else if args.sort_time { SortType::Time(time_type) }
+      else if args.sort_extension { SortType::Extension }
+      else if args.sort_version { SortType::Version }
+      else {
+        match args.sort {
+          Some(SortWord::None) => SortType::None,
+          Some(SortWord::Size) => SortType::Size,
+          Some(SortWord::Time) => SortType::Time(time_type),
+          Some(SortWord::Version) => SortType::Version,
+          Some(SortWord::Extension) => SortType::Extension,
+          Some(SortWord::Width) => SortType::Width,
+          // -u, -c and --time sort by their timestamp, unless -l is set
+          None if time_type != TimeType::Modification && format != Format::Long => SortType::Time(time_type),
+          None => SortType::Name
+        }
+      }
+    };
     let format_info: Option<FormatInfo> = {
-      if args.long { Some(FormatInfo::new()) }
+      if format == Format::Long || args.inode || args.size { Some(FormatInfo::new()) }
       else { None }
     };
 
-    let mut cwd_files:Vec<Rc<FileInfo>> = Vec::new();
-    cwd_files.reserve(128);
+    let id_cache: IdCache = {
+      if format == Format::Long && !args.numeric_uid_gid { IdCache::load() }
+      else { IdCache::new() }
+    };
+
+    let (block_size, file_block_size): (BlockSize, BlockSize) = {
+      if args.human_readable { (BlockSize::human(true), BlockSize::human(true)) }
+      else if args.si { (BlockSize::human(false), BlockSize::human(false)) }
+      else {
+        match args.block_size {
+          Some(block_size) => (block_size, block_size),
+          None => BlockSize::from_env()
+        }
+      }
+    };
+
+    let print_inode: bool = args.inode;
+    let print_block_size: bool = args.size;
+    let print_owner: bool = !args.long_without_owner;
+    let print_group: bool = !(args.long_without_group || args.no_group);
+    let print_author: bool = args.author;
+
+    let cwd_files:Vec<Rc<FileInfo>> = Vec::with_capacity(128);
 
     Self {
       args, 
@@ -59,11 +273,30 @@ impl WorkingSet {
       cwd_files,
       sorted_files: Vec::new(),
       loop_manager: LoopManager::new(),
-      print_info: PrintInfo::new(),
-      column_state: None,
+      print_info,
+      column_state: ColumnState::new(),
       exit_status: ExitStatus::default(),
       ignore_mode,
+      ignore_patterns,
+      hide_patterns,
+      git_ignore_mode,
+      git_ignore: GitIgnore::new(),
+      git_ignore_dir: None,
+      format,
+      style,
+      name_style,
+      dereference,
+      sort_type,
+      time_type,
       format_info,
+      id_cache,
+      block_size,
+      file_block_size,
+      print_inode,
+      print_block_size,
+      print_owner,
+      print_group,
+      print_author,
       abort_flag
     }
   }
@@ -72,11 +305,15 @@ impl WorkingSet {
     let cli_arg: bool = true;
     let paths: Vec<String> = self.args.paths.clone();
 
-    if paths.len() == 0 {
-      self.pending_dirs.queue_directory(Some("."), None, cli_arg);
+    if paths.is_empty() {
+      if self.args.directory {
+        gobble_file(self, ".", FileType::Directory, 0, cli_arg, None);
+      } else {
+        self.pending_dirs.queue_directory(Some("."), None, cli_arg);
+      }
     } 
-    for i in 0..paths.len() {
-      gobble_file(self, &paths[i], FileType::Unknown, 0, cli_arg, None);
+    for path in paths.iter() {
+      gobble_file(self, path, FileType::Unknown, 0, cli_arg, None);
     }
   }
 
@@ -85,16 +322,36 @@ impl WorkingSet {
     // self.sorted_files.clear(); 
     self.cwd_files.clear();
     
-    if self.args.long { 
+    if self.format == Format::Long || self.print_inode || self.print_block_size { 
       self.format_info = Some(FormatInfo::new());
     } else {
       self.format_info = None;
     }
   }
 
+  /// Warns about files, whose names only differ by invisible characters (--reveal-invisible)
+  pub fn warn_lookalike_names(&self) {
+    if !self.name_style.reveal_invisible {
+      return;
+    }
+
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for file in self.cwd_files.iter() {
+      let skeleton: String = visible_skeleton(&file.name);
+      match seen.get(skeleton.as_str()) {
+        Some(other) if *other != file.name => eprintln!(
+          "lrs: warning: {} looks identical to {}",
+          self.name_style.quote(&file.name),
+          self.name_style.quote(other)
+        ),
+        Some(_) => (),
+        None => { seen.insert(skeleton, &file.name); }
+      }
+    }
+  }
+
   pub fn sort_files(&mut self) {
-    // Todo: make this dependent of sort arguments!
-    self.sorted_files = self.cwd_files.sorted_files(SortType::Name);
+    self.sorted_files = self.cwd_files.sorted_files(self.sort_type, self.args.reverse);
   }
 
   pub fn extract_dirs_from_files(&mut self, dir_name: Option<&str>, command_line_arg: bool) {
@@ -102,7 +359,7 @@ impl WorkingSet {
     
     // Marker Entry
     if dir_name.is_some() && self.args.recursive {
-      self.pending_dirs.queue_directory(None, dir_name.clone(), false);
+      self.pending_dirs.queue_directory(None, dir_name, false);
     }
   
     for file_info in self.sorted_files.iter().rev() {
@@ -111,11 +368,12 @@ impl WorkingSet {
       if f.is_directory() && (!ignore_dot_and_dot_dot || !basename_is_dot_or_dot_dot(&f.name)) {
         /* File names cannot be empty, which is why chars.nth(0).unwrap() is safe to do,
         else there is a problem in the name allocation of the FileInfo object. */
-        if dir_name.is_none() || f.name.chars().nth(0).unwrap() == '/' {
-          self.pending_dirs.queue_directory(Some(&f.name), f.link_name.as_deref(), command_line_arg);
-        } else {
-          let name: String = file_name_concat(dir_name.unwrap(), &f.name); 
-          self.pending_dirs.queue_directory(Some(&name), f.link_name.as_deref(), command_line_arg);
+        match dir_name {
+          Some(dir_name) if !f.name.starts_with('/') => {
+            let name: String = file_name_concat(dir_name, &f.name); 
+            self.pending_dirs.queue_directory(Some(&name), f.link_name.as_deref(), command_line_arg);
+          }
+          _ => self.pending_dirs.queue_directory(Some(&f.name), f.link_name.as_deref(), command_line_arg)
         }
       }
     }
@@ -123,29 +381,61 @@ impl WorkingSet {
     self.sorted_files.retain(|f| f.upgrade().unwrap().file_type != FileType::ArgDirectory);
   }
 
+  /// Whether a directory entry is left out. Arguments on the command line are always listed
+  /// Only the name is looked at, so ignored entries are never stat'ed
   pub fn file_ignored(&self, file_name: &str) -> bool {
-    self.ignore_mode != IgnoreMode::IgnoreMinimal 
-    && file_name.starts_with('.')
-    && (self.ignore_mode == IgnoreMode::IgnoreDefault || dot_or_dot_dot(file_name))
+    (self.ignore_mode != IgnoreMode::IgnoreMinimal 
+      && file_name.starts_with('.')
+      && (self.ignore_mode == IgnoreMode::IgnoreDefault || dot_or_dot_dot(file_name)))
+    || (self.ignore_mode == IgnoreMode::IgnoreDefault
+      && self.hide_patterns.iter().any(|pattern| pattern.matches(file_name)))
+    || self.ignore_patterns.iter().any(|pattern| pattern.matches(file_name))
+  }
+
+  /// Whether an entry of the directory being read is ignored by git (--git-ignore).
+  /// is_dir is only called for rules of directories
+  pub fn git_ignored(&self, file_name: &str, is_dir: impl Fn() -> bool) -> bool {
+    match &self.git_ignore_dir {
+      Some(ignore_dir) if !dot_or_dot_dot(file_name) => ignore_dir.is_ignored(file_name, is_dir),
+      _ => false
+    }
+  }
+
+  /// Reads the ignore files applying to the entries of dir, unless already cached
+  pub fn enter_git_ignore_dir(&mut self, dir: &Path) {
+    self.git_ignore_dir = self.git_ignore.dir(dir);
   }
 
+  /// Number of columns of the widest layout fitting into the line, like in coreutils.
+  /// The widths of its columns are stored in column_state.line_fmt
   pub fn calculate_columns(&mut self, by_columns: bool) -> usize {
-    let print_info: &PrintInfo = &self.print_info;
-    
-    let file_count: usize  = self.sorted_files.len();
-    let max_idx = print_info.max_idx;
+    let widths: Vec<usize> = self.sorted_files.iter()
+      .map(|weak_file| file_width(&weak_file.upgrade().unwrap(), self))
+      .collect();
+    let file_count: usize = widths.len();
+    let line_length: usize = self.print_info.line_length;
     // max_idx is of type usize: therefore always >= 0
-    let max_cols: usize = if max_idx < file_count { max_idx } else { file_count }; 
+    let max_cols: usize = self.print_info.max_idx.min(file_count);
+    let column_state: &mut ColumnState = &mut self.column_state;
+
+    // Early exit: all files fit into a single line, which is the widest possible layout
+    if max_cols == file_count {
+      let line_fmt: Vec<usize> = widths.iter().enumerate()
+        .map(|(i, w)| MIN_COLUMN_WIDTH.max(if i + 1 == file_count { *w } else { w + 2 }))
+        .collect();
+      if line_fmt.iter().sum::<usize>() < line_length {
+        column_state.line_fmt = line_fmt;
+        return file_count;
+      }
+    }
 
-    let mut column_state: ColumnState = ColumnState::init_column_info(max_cols);
+    let max_cols: usize = ColumnState::max_feasible_columns(&widths, max_cols, line_length, by_columns);
+    column_state.init_column_info(max_cols);
     let columns: &mut Vec<ColumnInfo> = &mut column_state.columns;
     
-    for (file_i, weak_file) in self.sorted_files.iter().enumerate() {
-      let file = weak_file.upgrade().unwrap(); 
-      let name_length = file.width;
-      
-      for col in 0..max_cols {
-        if columns[col].valid_len {
+    for (file_i, name_length) in widths.iter().enumerate() {
+      for (col, column) in columns.iter_mut().enumerate().take(max_cols) {
+        if column.valid_len {
           let rows: usize = (file_count + col) / (col + 1);
           let idx: usize = if by_columns {
             file_i / rows
@@ -153,26 +443,25 @@ impl WorkingSet {
               file_i % (col + 1)
           };
 
-          let real_length: usize = if idx == col { name_length } else { name_length + 2};
+          let real_length: usize = if idx == col { *name_length } else { name_length + 2};
           
-          if columns[col].col_arr[idx] < real_length{
-            columns[col].line_len += real_length - columns[col].col_arr[idx];
-            columns[col].col_arr[idx] = real_length; 
-            columns[col].valid_len = columns[col].line_len < print_info.line_length;
+          if column.col_arr[idx] < real_length{
+            column.line_len += real_length - column.col_arr[idx];
+            column.col_arr[idx] = real_length; 
+            column.valid_len = column.line_len < line_length;
           }
         }
       }
     }
     
-    let mut cols = max_cols;
-    while cols > 1 {
+    let mut cols: usize = max_cols;
+    while cols > 1 && !columns[cols - 1].valid_len {
       cols -= 1;
-      if columns[cols].valid_len {
-        break
-      }
     }
     
-    self.column_state = Some(column_state);
-    return cols;
+    column_state.line_fmt.clear();
+    column_state.line_fmt.extend_from_slice(&columns[cols - 1].col_arr);
+    cols
   }
+
 }